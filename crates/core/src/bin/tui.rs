@@ -0,0 +1,186 @@
+//! Terminal dashboard: a `ratatui`/`crossterm` alternative to the
+//! `println!`-and-screenshots-folder output `main` produces. Runs its own
+//! `WindowEventProcessor` and renders the live foreground app plus today's
+//! apps ranked by accumulated active time, with a history view over other
+//! days. `--nogui` prints the same ranking once and exits, for scripting.
+
+use std::io::stdout;
+use std::time::Duration;
+
+use core::clocks::RealClocks;
+use core::tracker::window_processor::WindowEventProcessor;
+use core::tui::app::Dashboard;
+use core::tui::ui;
+
+use crossterm::event::{self, Event, KeyCode};
+use crossterm::execute;
+use crossterm::terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode};
+use ratatui::Terminal;
+use ratatui::backend::CrosstermBackend;
+use tokio::sync::broadcast;
+
+const REFRESH_INTERVAL: Duration = Duration::from_secs(2);
+const INPUT_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    dotenv::dotenv().ok();
+
+    let nogui = std::env::args().any(|arg| arg == "--nogui");
+
+    let config = core::config::Config::load().unwrap_or_default();
+    let db_pool =
+        core::db::pool::create_pool(&config.database_url, config.resolved_encryption_key().as_deref())
+            .await?;
+
+    let mut dashboard = Dashboard::new();
+    dashboard.refresh(&db_pool, &RealClocks).await?;
+
+    if nogui {
+        print_summary(&dashboard);
+        return Ok(());
+    }
+
+    let processor = WindowEventProcessor::new(db_pool.clone())
+        .with_idle_threshold(Duration::from_secs(config.idle_threshold_secs));
+    let events = processor.start_with_events();
+
+    run_tui(db_pool, dashboard, events).await
+}
+
+/// `--nogui`: prints the same ranking the live view's left pane shows, once,
+/// for scripting.
+fn print_summary(dashboard: &Dashboard) {
+    println!("{:<40}{:>8}", "App", "Active");
+    for app in dashboard.ranked() {
+        println!("{:<40}{:>8}", app.app_name, ui::format_duration(app.active_secs));
+    }
+}
+
+enum Action {
+    Quit,
+    ToggleView,
+    SelectNext,
+    SelectPrev,
+    OlderDay,
+    NewerDay,
+    StartSearch,
+    SearchChar(char),
+    SearchBackspace,
+    SearchSubmit,
+    SearchCancel,
+}
+
+async fn run_tui(
+    db_pool: sqlx::SqlitePool,
+    mut dashboard: Dashboard,
+    mut events: broadcast::Receiver<core::tracker::events::WindowEvent>,
+) -> anyhow::Result<()> {
+    let _terminal_guard = TerminalGuard::enter()?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(stdout()))?;
+    let mut refresh_ticker = tokio::time::interval(REFRESH_INTERVAL);
+
+    loop {
+        terminal.draw(|frame| ui::draw(frame, &dashboard))?;
+
+        tokio::select! {
+            event = events.recv() => {
+                match event {
+                    Ok(window_event) => dashboard.apply_event(&window_event),
+                    Err(broadcast::error::RecvError::Lagged(_)) => {}
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+            _ = refresh_ticker.tick() => {
+                if let Err(e) = dashboard.refresh(&db_pool, &RealClocks).await {
+                    eprintln!("Failed to refresh dashboard: {e}");
+                }
+            }
+            _ = tokio::time::sleep(INPUT_POLL_INTERVAL) => {
+                if let Some(action) = poll_input(dashboard.search_active)? {
+                    let needs_refresh = matches!(
+                        action,
+                        Action::ToggleView
+                            | Action::OlderDay
+                            | Action::NewerDay
+                            | Action::SelectNext
+                            | Action::SelectPrev
+                    );
+                    match action {
+                        Action::Quit => break,
+                        Action::ToggleView => dashboard.view = dashboard.view.toggle(),
+                        Action::SelectNext => dashboard.select_next(),
+                        Action::SelectPrev => dashboard.select_prev(),
+                        Action::OlderDay => dashboard.shift_history(1),
+                        Action::NewerDay => dashboard.shift_history(-1),
+                        Action::StartSearch => dashboard.start_search(),
+                        Action::SearchChar(c) => dashboard.push_search_char(c),
+                        Action::SearchBackspace => dashboard.pop_search_char(),
+                        Action::SearchCancel => dashboard.cancel_search(),
+                        Action::SearchSubmit => {
+                            if let Err(e) = dashboard.run_search(&db_pool).await {
+                                eprintln!("Search failed: {e}");
+                            }
+                        }
+                    }
+                    if needs_refresh {
+                        dashboard.refresh(&db_pool, &RealClocks).await.ok();
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Polls for a single key press without blocking the `tokio::select!` loop.
+/// While `search_active`, keys feed the typed query instead of navigation.
+fn poll_input(search_active: bool) -> anyhow::Result<Option<Action>> {
+    if !event::poll(Duration::from_millis(0))? {
+        return Ok(None);
+    }
+    let Event::Key(key) = event::read()? else {
+        return Ok(None);
+    };
+
+    if search_active {
+        return Ok(match key.code {
+            KeyCode::Esc => Some(Action::SearchCancel),
+            KeyCode::Enter => Some(Action::SearchSubmit),
+            KeyCode::Backspace => Some(Action::SearchBackspace),
+            KeyCode::Char(c) => Some(Action::SearchChar(c)),
+            _ => None,
+        });
+    }
+
+    Ok(match key.code {
+        KeyCode::Char('q') | KeyCode::Esc => Some(Action::Quit),
+        KeyCode::Tab => Some(Action::ToggleView),
+        KeyCode::Down | KeyCode::Char('j') => Some(Action::SelectNext),
+        KeyCode::Up | KeyCode::Char('k') => Some(Action::SelectPrev),
+        KeyCode::Left | KeyCode::Char('h') => Some(Action::OlderDay),
+        KeyCode::Right | KeyCode::Char('l') => Some(Action::NewerDay),
+        KeyCode::Char('/') => Some(Action::StartSearch),
+        _ => None,
+    })
+}
+
+/// Puts the terminal into raw mode + the alternate screen, and restores it
+/// on drop regardless of how `run_tui` exits (quit key, error, or panic).
+struct TerminalGuard;
+
+impl TerminalGuard {
+    fn enter() -> anyhow::Result<Self> {
+        enable_raw_mode()?;
+        execute!(stdout(), EnterAlternateScreen)?;
+        Ok(Self)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = disable_raw_mode();
+        let _ = execute!(stdout(), LeaveAlternateScreen);
+    }
+}
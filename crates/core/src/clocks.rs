@@ -0,0 +1,93 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Abstracts over wall-clock time so the capture pipeline can be driven
+/// deterministically under test, instead of depending implicitly on SQLite's
+/// `datetime('now')` or a bare `SystemTime::now()` call at each insert site.
+pub trait Clocks: Send + Sync {
+    /// Unix timestamp, in seconds, to stamp inserts with.
+    fn now_unix_secs(&self) -> i64;
+    /// Monotonic instant, used for debouncing and interval scheduling.
+    fn monotonic(&self) -> Instant;
+}
+
+/// Clocks backed by the real system clock.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RealClocks;
+
+impl Clocks for RealClocks {
+    fn now_unix_secs(&self) -> i64 {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0)
+    }
+
+    fn monotonic(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// Clocks a test can drive manually.
+///
+/// `monotonic()` is a fixed base `Instant` plus an offset that `advance()`
+/// moves in lockstep with the simulated unix-seconds clock, so debouncing
+/// logic keyed off `Clocks::monotonic` (e.g. `WindowEventProcessor`'s
+/// screenshot interval) is just as deterministic under test as event
+/// ordering. Screenshot rows are not stamped from `Clocks`: their
+/// `created_at` comes from SQLite's `CURRENT_TIMESTAMP` default.
+#[derive(Debug, Clone)]
+pub struct SimulatedClocks {
+    unix_secs: Arc<Mutex<i64>>,
+    monotonic_base: Instant,
+    monotonic_offset: Arc<Mutex<Duration>>,
+}
+
+impl SimulatedClocks {
+    pub fn new(start_unix_secs: i64) -> Self {
+        Self {
+            unix_secs: Arc::new(Mutex::new(start_unix_secs)),
+            monotonic_base: Instant::now(),
+            monotonic_offset: Arc::new(Mutex::new(Duration::ZERO)),
+        }
+    }
+
+    /// Advances the simulated clock by `secs` and returns the new value.
+    pub fn advance(&self, secs: i64) -> i64 {
+        let mut now = self.unix_secs.lock().unwrap();
+        *now += secs;
+        *self.monotonic_offset.lock().unwrap() += Duration::from_secs(secs.max(0) as u64);
+        *now
+    }
+}
+
+impl Clocks for SimulatedClocks {
+    fn now_unix_secs(&self) -> i64 {
+        *self.unix_secs.lock().unwrap()
+    }
+
+    fn monotonic(&self) -> Instant {
+        self.monotonic_base + *self.monotonic_offset.lock().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn simulated_clock_advances_on_demand() {
+        let clock = SimulatedClocks::new(1_000);
+        assert_eq!(clock.now_unix_secs(), 1_000);
+        assert_eq!(clock.advance(30), 1_030);
+        assert_eq!(clock.now_unix_secs(), 1_030);
+    }
+
+    #[test]
+    fn simulated_monotonic_advances_in_lockstep_with_unix_secs() {
+        let clock = SimulatedClocks::new(1_000);
+        let start = clock.monotonic();
+        clock.advance(10);
+        assert_eq!(clock.monotonic() - start, Duration::from_secs(10));
+    }
+}
@@ -5,6 +5,72 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Config {
     pub database_url: String,
+    /// Passphrase for SQLCipher-at-rest encryption of the SQLite store. Only
+    /// takes effect when built with the `sqlcipher` feature; ignored otherwise.
+    /// Prefer the `INTIME_DB_KEY` env var over committing this to disk.
+    #[serde(default)]
+    pub encryption_key: Option<String>,
+    /// Directory `db::backup::snapshot` writes periodic online backups into.
+    #[serde(default = "default_backup_dir")]
+    pub backup_dir: PathBuf,
+    /// How often the background backup task snapshots the live database.
+    #[serde(default = "default_backup_interval_hours")]
+    pub backup_interval_hours: u64,
+    /// Number of snapshots to retain; older ones are pruned after each run.
+    #[serde(default = "default_backup_keep")]
+    pub backup_keep: usize,
+    /// How long the machine must be without input before it's considered
+    /// idle and time accounting pauses.
+    #[serde(default = "default_idle_threshold_secs")]
+    pub idle_threshold_secs: u64,
+    /// Hamming distance (out of the 64 bits `phash::dhash` produces) below
+    /// which a newly captured screenshot is treated as unchanged from the
+    /// previous one for that app, and skipped in favor of an `is_marker`
+    /// placeholder row. With `screenshot_to_disk` set, such a capture is
+    /// dropped and no marker is written.
+    #[serde(default = "default_screenshot_hash_distance_threshold")]
+    pub screenshot_hash_distance_threshold: u32,
+    /// Where captures land: `true` keeps writing `screenshots/*.png` files
+    /// like before; `false` stores them as BLOBs via
+    /// `db::crud::create_screenshot` instead, trading disk clutter for a
+    /// larger database.
+    #[serde(default = "default_screenshot_to_disk")]
+    pub screenshot_to_disk: bool,
+    /// Whether intime registers itself to launch at login, so tracking
+    /// resumes automatically after a reboot. Applied via `set_auto_launch`.
+    #[serde(default = "default_autostart")]
+    pub autostart: bool,
+}
+
+fn default_backup_dir() -> PathBuf {
+    dirs::config_dir()
+        .expect("Failed to get config dir, might be OS related, please issue a PR for this")
+        .join("intime")
+        .join("backups")
+}
+
+fn default_backup_interval_hours() -> u64 {
+    24
+}
+
+fn default_backup_keep() -> usize {
+    7
+}
+
+fn default_idle_threshold_secs() -> u64 {
+    120
+}
+
+fn default_screenshot_hash_distance_threshold() -> u32 {
+    10
+}
+
+fn default_screenshot_to_disk() -> bool {
+    true
+}
+
+fn default_autostart() -> bool {
+    true
 }
 
 impl Default for Config {
@@ -18,11 +84,28 @@ impl Default for Config {
                 .to_str()
                 .unwrap()
                 .to_string(),
+            encryption_key: None,
+            backup_dir: default_backup_dir(),
+            backup_interval_hours: default_backup_interval_hours(),
+            backup_keep: default_backup_keep(),
+            idle_threshold_secs: default_idle_threshold_secs(),
+            screenshot_hash_distance_threshold: default_screenshot_hash_distance_threshold(),
+            screenshot_to_disk: default_screenshot_to_disk(),
+            autostart: default_autostart(),
         }
     }
 }
 
 impl Config {
+    /// Resolves the encryption key to use: an explicit config value takes
+    /// precedence, falling back to `INTIME_DB_KEY` so the passphrase doesn't
+    /// need to live in the config file on disk.
+    pub fn resolved_encryption_key(&self) -> Option<String> {
+        self.encryption_key
+            .clone()
+            .or_else(|| std::env::var("INTIME_DB_KEY").ok())
+    }
+
     fn config_path() -> PathBuf {
         dirs::config_dir()
             .expect("Failed to get config dir, might be OS related, please issue a PR for this")
@@ -49,3 +132,38 @@ impl Config {
         std::fs::write(&path, content).map_err(|e| format!("Failed to write config file: {}", e))
     }
 }
+
+/// Registers or unregisters intime as a start-on-login entry, to match
+/// `Config::autostart`. Queries the current state first and only calls
+/// into the OS's registration machinery when it disagrees with `enabled`,
+/// so repeated config saves don't thrash the registry/launch agent.
+pub fn set_auto_launch(enabled: bool) -> Result<(), String> {
+    let exe = std::env::current_exe()
+        .map_err(|e| format!("Failed to resolve current executable: {}", e))?;
+    let exe_path = exe
+        .to_str()
+        .ok_or_else(|| "Executable path is not valid UTF-8".to_string())?;
+
+    let auto_launch = auto_launch::AutoLaunchBuilder::new()
+        .set_app_name("intime")
+        .set_app_path(exe_path)
+        .build()
+        .map_err(|e| format!("Failed to build auto-launch entry: {}", e))?;
+
+    let is_enabled = auto_launch
+        .is_enabled()
+        .map_err(|e| format!("Failed to query auto-launch state: {}", e))?;
+    if is_enabled == enabled {
+        return Ok(());
+    }
+
+    if enabled {
+        auto_launch
+            .enable()
+            .map_err(|e| format!("Failed to enable auto-launch: {}", e))
+    } else {
+        auto_launch
+            .disable()
+            .map_err(|e| format!("Failed to disable auto-launch: {}", e))
+    }
+}
@@ -0,0 +1,49 @@
+use anyhow::{Context, Result};
+use sqlx::{Executor, SqlitePool};
+use std::path::{Path, PathBuf};
+
+/// Performs an online snapshot of the live pool via `VACUUM INTO`, which is
+/// safe to run against a WAL-journaled database while the tracker keeps
+/// writing to it. Returns the path of the snapshot that was written.
+pub async fn snapshot(pool: &SqlitePool, backup_dir: &Path) -> Result<PathBuf> {
+    std::fs::create_dir_all(backup_dir)
+        .with_context(|| format!("creating backup dir {}", backup_dir.display()))?;
+
+    let timestamp = chrono::Utc::now().to_rfc3339().replace(':', "-");
+    let dest = backup_dir.join(format!("intime_{timestamp}.db"));
+
+    let escaped_dest = dest.display().to_string().replace('\'', "''");
+    let mut conn = pool.acquire().await?;
+    conn.execute(sqlx::query(&format!("VACUUM INTO '{escaped_dest}'")))
+        .await
+        .with_context(|| format!("VACUUM INTO {}", dest.display()))?;
+
+    Ok(dest)
+}
+
+/// Deletes the oldest snapshots in `backup_dir` beyond `keep`, keeping the
+/// most recent ones (snapshot filenames sort lexicographically by their
+/// RFC3339 timestamp).
+pub fn prune_backups(backup_dir: &Path, keep: usize) -> Result<()> {
+    let mut entries: Vec<PathBuf> = std::fs::read_dir(backup_dir)?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().and_then(|e| e.to_str()) == Some("db"))
+        .collect();
+    entries.sort();
+
+    let excess = entries.len().saturating_sub(keep);
+    for stale in &entries[..excess] {
+        let _ = std::fs::remove_file(stale);
+    }
+
+    Ok(())
+}
+
+/// Truncates the WAL file so it doesn't grow unbounded after a burst of
+/// writes; harmless to call on a schedule alongside backups.
+pub async fn checkpoint_truncate(pool: &SqlitePool) -> Result<()> {
+    let mut conn = pool.acquire().await?;
+    conn.execute("PRAGMA wal_checkpoint(TRUNCATE);").await?;
+    Ok(())
+}
@@ -1,16 +1,25 @@
 use crate::{
-    db::models::{Screenshot, WindowEvent},
+    db::models::{AppDuration, NewScreenshot, Screenshot, WindowEvent},
     tracker::events::WindowEventType,
 };
 
 use super::models::App;
 use anyhow::Result;
 
-pub async fn get_saved_app(db_pool: &sqlx::SqlitePool, name: &str) -> Option<App> {
+/// Looks up an app by its compound identity `(name, command_line)`, since
+/// distinct workloads can share one executable name (multiple Electron
+/// apps, different `python.exe` scripts) and must not collapse into one
+/// saved row. See `migrations/sqlite/0005_app_identity_compound_key.sql`.
+pub async fn get_saved_app(
+    db_pool: &sqlx::SqlitePool,
+    name: &str,
+    command_line: Option<&str>,
+) -> Option<App> {
     sqlx::query_as!(
         App,
-        "SELECT id, name, path, icon FROM app WHERE name = ?",
+        "SELECT id, name, path, icon, command_line, parent_name FROM app WHERE name = ? AND command_line IS ?",
         name,
+        command_line,
     )
     .fetch_optional(db_pool)
     .await
@@ -20,10 +29,12 @@ pub async fn get_saved_app(db_pool: &sqlx::SqlitePool, name: &str) -> Option<App
 
 pub async fn create_app(db_pool: &sqlx::SqlitePool, app: &App) -> Result<(), sqlx::Error> {
     sqlx::query!(
-        "INSERT INTO app (name, path, icon) VALUES (?, ?, ?)",
+        "INSERT INTO app (name, path, icon, command_line, parent_name) VALUES (?, ?, ?, ?, ?)",
         app.name,
         app.path,
-        app.icon
+        app.icon,
+        app.command_line,
+        app.parent_name
     )
     .execute(db_pool)
     .await?;
@@ -33,11 +44,39 @@ pub async fn create_app(db_pool: &sqlx::SqlitePool, app: &App) -> Result<(), sql
 pub async fn update_app_path(
     db_pool: &sqlx::SqlitePool,
     name: &str,
+    command_line: Option<&str>,
     new_path: &str,
 ) -> Result<(), sqlx::Error> {
-    sqlx::query!("UPDATE app SET path = ? WHERE name = ?", new_path, name,)
-        .execute(db_pool)
-        .await?;
+    sqlx::query!(
+        "UPDATE app SET path = ? WHERE name = ? AND command_line IS ?",
+        new_path,
+        name,
+        command_line,
+    )
+    .execute(db_pool)
+    .await?;
+    Ok(())
+}
+
+/// Refreshes the parent name recorded for a saved app, since it can change
+/// across runs of the same executable (e.g. a different parent shell) even
+/// when its `(name, command_line)` identity doesn't. `command_line` itself
+/// is part of that identity now, so a change there means a different app
+/// row rather than an update to this one.
+pub async fn update_app_identity(
+    db_pool: &sqlx::SqlitePool,
+    name: &str,
+    command_line: Option<&str>,
+    parent_name: Option<&str>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "UPDATE app SET parent_name = ? WHERE name = ? AND command_line IS ?",
+        parent_name,
+        name,
+        command_line,
+    )
+    .execute(db_pool)
+    .await?;
     Ok(())
 }
 
@@ -79,19 +118,25 @@ pub async fn create_window_event_with_timestamp(
     Ok(())
 }
 
+/// Inserts a screenshot and returns its row id, so callers can broadcast it
+/// (e.g. as a `tracker::events::ScreenshotCaptured` notification) without a
+/// follow-up query.
 pub async fn create_screenshot(
     db_pool: &sqlx::Pool<sqlx::Sqlite>,
-    image: Vec<u8>,
-    app_id: i64,
-) -> Result<(), sqlx::Error> {
-    sqlx::query!(
-        "INSERT INTO screenshot (app_id, screenshot) VALUES (?, ?)",
-        app_id,
-        image
+    screenshot: &NewScreenshot,
+) -> Result<i64, sqlx::Error> {
+    let result = sqlx::query!(
+        "INSERT INTO screenshot (app_id, hwnd, window_title, screenshot, phash, is_marker) VALUES (?, ?, ?, ?, ?, ?)",
+        screenshot.app_id,
+        screenshot.hwnd,
+        screenshot.window_title,
+        screenshot.png,
+        screenshot.phash,
+        screenshot.is_marker,
     )
     .execute(db_pool)
     .await?;
-    Ok(())
+    Ok(result.last_insert_rowid())
 }
 
 pub async fn get_window_events_secs(
@@ -133,6 +178,53 @@ pub async fn get_window_events_secs(
     Ok(items)
 }
 
+/// Full-text search over window titles and owning app names via the
+/// contentless `window_event_fts` index, optionally restricted to a time
+/// window, ordered by FTS5 `rank` (best match first).
+pub async fn search_window_events(
+    db_pool: &sqlx::Pool<sqlx::Sqlite>,
+    query: &str,
+    start_sec: Option<i64>,
+    end_sec: Option<i64>,
+    limit: i64,
+) -> Result<Vec<WindowEvent>> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT we.app_id as app_id,
+               a.name as app_name,
+               we.window_title as window_title,
+               we.event_type as event_type,
+         CAST(strftime('%s', we.created_at) AS INTEGER) as "created_at_sec!: i64"
+        FROM window_event_fts f
+        JOIN window_event we ON we.id = f.rowid
+        JOIN app a ON a.id = we.app_id
+        WHERE window_event_fts MATCH ?1
+          AND (?2 IS NULL OR we.created_at >= datetime(?2, 'unixepoch'))
+          AND (?3 IS NULL OR we.created_at <= datetime(?3, 'unixepoch'))
+        ORDER BY f.rank
+        LIMIT ?4
+        "#,
+        query,
+        start_sec,
+        end_sec,
+        limit
+    )
+    .fetch_all(db_pool)
+    .await?;
+
+    let items = rows
+        .into_iter()
+        .map(|r| WindowEvent {
+            app_id: r.app_id,
+            app_name: r.app_name,
+            window_title: r.window_title,
+            event_type: r.event_type,
+            created_at_sec: r.created_at_sec,
+        })
+        .collect();
+    Ok(items)
+}
+
 pub async fn get_nearest_screenshot(
     db_pool: &sqlx::Pool<sqlx::Sqlite>,
     ts_sec: i64,
@@ -143,7 +235,10 @@ pub async fn get_nearest_screenshot(
         r#"
         SELECT id as "id!: i64",
                app_id as "app_id!: i64",
+               hwnd, window_title,
                screenshot as "png: Vec<u8>",
+               phash,
+               is_marker as "is_marker!: bool",
                CAST(strftime('%s', created_at) AS INTEGER) as "created_at_sec!: i64"
         FROM screenshot
         WHERE (?1 IS NULL OR app_id = ?1)
@@ -160,7 +255,11 @@ pub async fn get_nearest_screenshot(
     let newer: Option<Screenshot> = newer_row.map(|r| Screenshot {
         id: r.id,
         app_id: r.app_id,
+        hwnd: r.hwnd,
+        window_title: r.window_title,
         created_at_sec: r.created_at_sec,
+        phash: r.phash,
+        is_marker: r.is_marker,
         png: r.png,
     });
 
@@ -168,7 +267,10 @@ pub async fn get_nearest_screenshot(
         r#"
         SELECT id as "id!: i64",
                app_id as "app_id!: i64",
+               hwnd, window_title,
                screenshot as "png: Vec<u8>",
+               phash,
+               is_marker as "is_marker!: bool",
                CAST(strftime('%s', created_at) AS INTEGER) as "created_at_sec!: i64"
         FROM screenshot
         WHERE (?1 IS NULL OR app_id = ?1)
@@ -185,7 +287,11 @@ pub async fn get_nearest_screenshot(
     let older: Option<Screenshot> = older_row.map(|r| Screenshot {
         id: r.id,
         app_id: r.app_id,
+        hwnd: r.hwnd,
+        window_title: r.window_title,
         created_at_sec: r.created_at_sec,
+        phash: r.phash,
+        is_marker: r.is_marker,
         png: r.png,
     });
 
@@ -200,10 +306,150 @@ pub async fn get_nearest_screenshot(
         }
     };
 
-    Ok(pick.map(|r| Screenshot {
-        id: r.id,
-        app_id: r.app_id,
-        created_at_sec: r.created_at_sec,
-        png: r.png,
-    }))
+    Ok(pick)
+}
+
+/// A gap between consecutive window_events longer than this isn't counted
+/// towards the earlier event's app. Suspensions (idle, lock, minimize,
+/// pause) close their interval with a `SUSPENDED` row of their own (see
+/// `WindowEventProcessor::handle_presence_transition`), whose following gap
+/// is skipped below; this cap bounds any other long gap between rows.
+const MAX_GAP_SECS: i64 = 300;
+
+/// Ranks apps by accumulated active time over `[start_sec, end_sec)`, for the
+/// `tui` dashboard and any other aggregate view. Active time is approximated
+/// as the gap between one window_event and the next (or `end_sec`, for the
+/// last event in range), capped at `MAX_GAP_SECS`.
+pub async fn get_app_durations_secs(
+    db_pool: &sqlx::Pool<sqlx::Sqlite>,
+    start_sec: i64,
+    end_sec: i64,
+) -> Result<Vec<AppDuration>> {
+    let events = get_window_events_secs(db_pool, start_sec, end_sec, i64::MAX).await?;
+
+    let mut totals: std::collections::HashMap<i64, (String, i64)> = std::collections::HashMap::new();
+    for pair in events.windows(2) {
+        let (current, next) = (&pair[0], &pair[1]);
+        // A `SUSPENDED` row marks the instant accounting paused, not a real
+        // foreground window — the gap after it is suspended time, not
+        // active time for whatever app happened to hold that row's app_id.
+        if current.event_type == "SUSPENDED" {
+            continue;
+        }
+        let gap = (next.created_at_sec - current.created_at_sec).clamp(0, MAX_GAP_SECS);
+        let entry = totals
+            .entry(current.app_id)
+            .or_insert_with(|| (current.app_name.clone(), 0));
+        entry.1 += gap;
+    }
+    if let Some(last) = events.last() {
+        if last.event_type != "SUSPENDED" {
+            let gap = (end_sec - last.created_at_sec).clamp(0, MAX_GAP_SECS);
+            let entry = totals
+                .entry(last.app_id)
+                .or_insert_with(|| (last.app_name.clone(), 0));
+            entry.1 += gap;
+        }
+    }
+
+    let mut ranked: Vec<AppDuration> = totals
+        .into_iter()
+        .map(|(app_id, (app_name, active_secs))| AppDuration {
+            app_id,
+            app_name,
+            active_secs,
+        })
+        .collect();
+    ranked.sort_by(|a, b| b.active_secs.cmp(&a.active_secs));
+    Ok(ranked)
+}
+
+/// Fetches the most recent window_events for a single app, newest first, for
+/// the `tui` dashboard's detail pane.
+pub async fn get_recent_events_for_app(
+    db_pool: &sqlx::Pool<sqlx::Sqlite>,
+    app_id: i64,
+    limit: i64,
+) -> Result<Vec<WindowEvent>> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT we.app_id as app_id,
+               a.name as app_name,
+               we.window_title as window_title,
+               we.event_type as event_type,
+         CAST(strftime('%s', we.created_at) AS INTEGER) as "created_at_sec!: i64"
+        FROM window_event we
+        JOIN app a ON a.id = we.app_id
+        WHERE we.app_id = ?1
+        ORDER BY we.created_at DESC
+        LIMIT ?2
+        "#,
+        app_id,
+        limit
+    )
+    .fetch_all(db_pool)
+    .await?;
+
+    let items = rows
+        .into_iter()
+        .map(|r| WindowEvent {
+            app_id: r.app_id,
+            app_name: r.app_name,
+            window_title: r.window_title,
+            event_type: r.event_type,
+            created_at_sec: r.created_at_sec,
+        })
+        .collect();
+    Ok(items)
+}
+
+/// Fetches one page of screenshots in a time range, ordered by `id`
+/// ascending (insertion order), cursoring on `id` so a timelapse exporter can stream the whole
+/// range without ever loading it into memory at once.
+pub async fn get_screenshots_page(
+    db_pool: &sqlx::Pool<sqlx::Sqlite>,
+    start_sec: i64,
+    end_sec: i64,
+    app_id: Option<i64>,
+    after_id: i64,
+    page_size: i64,
+) -> Result<Vec<Screenshot>> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT id as "id!: i64",
+               app_id as "app_id!: i64",
+               hwnd, window_title,
+               screenshot as "png: Vec<u8>",
+               phash,
+               is_marker as "is_marker!: bool",
+               CAST(strftime('%s', created_at) AS INTEGER) as "created_at_sec!: i64"
+        FROM screenshot
+        WHERE (?1 IS NULL OR app_id = ?1)
+          AND created_at BETWEEN datetime(?2, 'unixepoch') AND datetime(?3, 'unixepoch')
+          AND id > ?4
+        ORDER BY id ASC
+        LIMIT ?5
+        "#,
+        app_id,
+        start_sec,
+        end_sec,
+        after_id,
+        page_size
+    )
+    .fetch_all(db_pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|r| Screenshot {
+            id: r.id,
+            app_id: r.app_id,
+            hwnd: r.hwnd,
+            window_title: r.window_title,
+            created_at_sec: r.created_at_sec,
+            phash: r.phash,
+            is_marker: r.is_marker,
+            png: r.png,
+        })
+        .collect())
 }
@@ -0,0 +1,13 @@
+//! SQLite-only for now: a backend-agnostic `Database` abstraction over
+//! `sqlx::Any` (so a central server could aggregate several machines'
+//! history into Postgres) was attempted and reverted as dead code that had
+//! no real call site and had already drifted out of sync with `crud`'s
+//! `(name, command_line)` app identity — see the now-deleted `db::any` in
+//! this crate's history. Not delivered; `create_pool` only opens `sqlite:`
+//! URLs. Reintroducing it needs an actual consumer (e.g. a
+//! config-selectable backend wired through `src-tauri`) alongside it, not
+//! a parallel module nobody calls.
+pub mod backup;
+pub mod crud;
+pub mod models;
+pub mod pool;
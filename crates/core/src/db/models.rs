@@ -2,15 +2,21 @@ use serde::Serialize;
 use sqlx::FromRow;
 
 #[derive(Debug, FromRow)]
-pub struct DBApp {
+pub struct App {
     pub id: Option<i64>,
     pub name: String,
     pub path: String,
     pub icon: Option<Vec<u8>>,
+    /// The owning process's command line, when it could be read. See
+    /// `tracker::events::WindowInfo::command_line`.
+    pub command_line: Option<String>,
+    /// Basename of the parent process's executable, when it could be
+    /// resolved. See `tracker::events::WindowInfo::parent_name`.
+    pub parent_name: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
-pub struct WindowEventRow {
+pub struct WindowEvent {
     pub app_id: i64,
     pub app_name: String,
     pub window_title: String,
@@ -19,9 +25,40 @@ pub struct WindowEventRow {
 }
 
 #[derive(Debug, Serialize)]
-pub struct ScreenshotBlob {
+pub struct Screenshot {
     pub id: i64,
     pub app_id: i64,
+    pub hwnd: Option<i64>,
+    pub window_title: Option<String>,
     pub created_at_sec: i64,
+    /// 64-bit `phash::dhash` of `png`, used to dedupe near-identical
+    /// consecutive captures. `None` for screenshots written before this
+    /// column existed.
+    pub phash: Option<i64>,
+    /// True for a "still active, nothing changed" placeholder written
+    /// instead of a full frame when `phash` was close enough to the previous
+    /// capture for the same app; `png` is empty for these.
+    pub is_marker: bool,
     pub png: Vec<u8>,
 }
+
+/// Parameters for inserting one screenshot row via `crud::create_screenshot`.
+/// `png` is empty when `is_marker` is set.
+#[derive(Debug)]
+pub struct NewScreenshot {
+    pub app_id: i64,
+    pub hwnd: i64,
+    pub window_title: String,
+    pub phash: i64,
+    pub is_marker: bool,
+    pub png: Vec<u8>,
+}
+
+/// One app's accumulated active time over a queried range, as returned by
+/// `db::crud::get_app_durations_secs`.
+#[derive(Debug, Clone, Serialize)]
+pub struct AppDuration {
+    pub app_id: i64,
+    pub app_name: String,
+    pub active_secs: i64,
+}
@@ -1,17 +1,79 @@
 use sqlx::sqlite::{SqlitePool, SqlitePoolOptions};
 use std::{str::FromStr, time::Duration};
 
-pub async fn create_pool(db_url: &str) -> Result<SqlitePool, sqlx::Error> {
+/// Opens (creating if necessary) the SQLite-backed pool and runs pending migrations.
+///
+/// `encryption_key`, when `Some`, is applied via `PRAGMA key` on every pooled
+/// connection before migrations run. It is only meaningful when this crate is
+/// built against a SQLCipher-enabled libsqlite3 (the `sqlcipher` feature); on a
+/// stock SQLite build the key is ignored so callers don't need to special-case it.
+pub async fn create_pool(
+    db_url: &str,
+    encryption_key: Option<&str>,
+) -> Result<SqlitePool, sqlx::Error> {
+    let key = encryption_key.map(|k| k.to_string());
+
     let pool = SqlitePoolOptions::new()
         .max_connections(8)
         .acquire_timeout(Duration::from_secs(5))
+        .after_connect(move |conn, _meta| {
+            let key = key.clone();
+            Box::pin(async move {
+                #[cfg(feature = "sqlcipher")]
+                if let Some(key) = key {
+                    key_connection(conn, &key).await?;
+                }
+                #[cfg(not(feature = "sqlcipher"))]
+                let _ = key;
+                Ok(())
+            })
+        })
         .connect_with(
             sqlx::sqlite::SqliteConnectOptions::from_str(db_url)?
                 .foreign_keys(true)
                 .create_if_missing(true)
                 .journal_mode(sqlx::sqlite::SqliteJournalMode::Wal),
         )
-        .await?;
-    sqlx::migrate!("../../migrations").run(&pool).await?;
+        .await
+        .map_err(explain_wrong_key)?;
+    sqlx::migrate!("../../migrations/sqlite").run(&pool).await?;
     Ok(pool)
 }
+
+/// Applies `PRAGMA key` to a freshly opened connection. Only compiled when linked
+/// against SQLCipher; calling it against a stock libsqlite3 build fails with
+/// "not an error" since the pragma is a no-op there.
+#[cfg(feature = "sqlcipher")]
+async fn key_connection(
+    conn: &mut sqlx::sqlite::SqliteConnection,
+    key: &str,
+) -> Result<(), sqlx::Error> {
+    use sqlx::Executor;
+    conn.execute(format!("PRAGMA key = '{}';", key.replace('\'', "''")).as_str())
+        .await?;
+    Ok(())
+}
+
+/// Rotates the passphrase on an already-open, keyed database via `PRAGMA rekey`.
+#[cfg(feature = "sqlcipher")]
+pub async fn change_key(pool: &SqlitePool, new_key: &str) -> Result<(), sqlx::Error> {
+    use sqlx::Executor;
+    let mut conn = pool.acquire().await?;
+    conn.execute(format!("PRAGMA rekey = '{}';", new_key.replace('\'', "''")).as_str())
+        .await?;
+    Ok(())
+}
+
+/// SQLCipher reports a wrong passphrase as a generic "file is not a database"
+/// error; this logs a message naming the likely cause (a wrong or unneeded
+/// key) and returns the error unchanged.
+fn explain_wrong_key(err: sqlx::Error) -> sqlx::Error {
+    if let sqlx::Error::Database(ref db_err) = err {
+        if db_err.message().contains("file is not a database") {
+            eprintln!(
+                "Failed to open database: wrong encryption key, or the database is not encrypted"
+            );
+        }
+    }
+    err
+}
@@ -0,0 +1,2 @@
+mod overlay;
+pub mod timelapse;
@@ -0,0 +1,147 @@
+use image::{Rgba, RgbaImage};
+
+/// Tiny built-in 3x5 bitmap font, just enough to burn a timestamp/title
+/// label into a timelapse frame without pulling in a font-rendering
+/// dependency. Each row is 3 bits, MSB first (leftmost pixel). Unsupported
+/// characters (anything outside digits, letters and a handful of
+/// punctuation) render as blank space.
+fn glyph(c: char) -> [u8; 5] {
+    match c.to_ascii_uppercase() {
+        '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => [0b111, 0b001, 0b111, 0b100, 0b111],
+        '3' => [0b111, 0b001, 0b111, 0b001, 0b111],
+        '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => [0b111, 0b100, 0b111, 0b001, 0b111],
+        '6' => [0b111, 0b100, 0b111, 0b101, 0b111],
+        '7' => [0b111, 0b001, 0b001, 0b001, 0b001],
+        '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => [0b111, 0b101, 0b111, 0b001, 0b111],
+        'A' => [0b111, 0b101, 0b111, 0b101, 0b101],
+        'B' => [0b110, 0b101, 0b110, 0b101, 0b110],
+        'C' => [0b111, 0b100, 0b100, 0b100, 0b111],
+        'D' => [0b110, 0b101, 0b101, 0b101, 0b110],
+        'E' => [0b111, 0b100, 0b111, 0b100, 0b111],
+        'F' => [0b111, 0b100, 0b111, 0b100, 0b100],
+        'G' => [0b111, 0b100, 0b101, 0b101, 0b111],
+        'H' => [0b101, 0b101, 0b111, 0b101, 0b101],
+        'I' => [0b111, 0b010, 0b010, 0b010, 0b111],
+        'J' => [0b001, 0b001, 0b001, 0b101, 0b111],
+        'K' => [0b101, 0b101, 0b110, 0b101, 0b101],
+        'L' => [0b100, 0b100, 0b100, 0b100, 0b111],
+        'M' => [0b101, 0b111, 0b101, 0b101, 0b101],
+        'N' => [0b101, 0b111, 0b111, 0b111, 0b101],
+        'O' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        'P' => [0b111, 0b101, 0b111, 0b100, 0b100],
+        'Q' => [0b111, 0b101, 0b101, 0b111, 0b001],
+        'R' => [0b111, 0b101, 0b111, 0b101, 0b101],
+        'S' => [0b111, 0b100, 0b111, 0b001, 0b111],
+        'T' => [0b111, 0b010, 0b010, 0b010, 0b010],
+        'U' => [0b101, 0b101, 0b101, 0b101, 0b111],
+        'V' => [0b101, 0b101, 0b101, 0b101, 0b010],
+        'W' => [0b101, 0b101, 0b101, 0b111, 0b101],
+        'X' => [0b101, 0b101, 0b010, 0b101, 0b101],
+        'Y' => [0b101, 0b101, 0b010, 0b010, 0b010],
+        'Z' => [0b111, 0b001, 0b010, 0b100, 0b111],
+        '-' => [0b000, 0b000, 0b111, 0b000, 0b000],
+        ':' => [0b000, 0b010, 0b000, 0b010, 0b000],
+        '.' => [0b000, 0b000, 0b000, 0b000, 0b010],
+        '_' => [0b000, 0b000, 0b000, 0b000, 0b111],
+        _ => [0b000, 0b000, 0b000, 0b000, 0b000],
+    }
+}
+
+const GLYPH_WIDTH: u32 = 3;
+const GLYPH_HEIGHT: u32 = 5;
+const SCALE: u32 = 2;
+const GLYPH_SPACING: u32 = SCALE;
+
+/// Burns `text` into the bottom-left corner of `image` over a translucent
+/// backing bar, so a timelapse frame is still identifiable once it's out of
+/// its original time range context.
+pub fn draw_overlay(image: &mut RgbaImage, text: &str) {
+    if text.is_empty() || image.width() == 0 || image.height() == 0 {
+        return;
+    }
+
+    let char_width = GLYPH_WIDTH * SCALE + GLYPH_SPACING;
+    let bar_height = (GLYPH_HEIGHT * SCALE + GLYPH_SPACING * 2).min(image.height());
+    let bar_width = (char_width * text.chars().count() as u32 + GLYPH_SPACING).min(image.width());
+    let y0 = image.height() - bar_height;
+
+    for y in y0..image.height() {
+        for x in 0..bar_width {
+            blend_pixel(image, x, y, Rgba([0, 0, 0, 160]));
+        }
+    }
+
+    let mut x_cursor = GLYPH_SPACING;
+    for ch in text.chars() {
+        if x_cursor + GLYPH_WIDTH * SCALE > image.width() {
+            break;
+        }
+        for (row, bits) in glyph(ch).iter().enumerate() {
+            for col in 0..GLYPH_WIDTH {
+                if bits & (1 << (GLYPH_WIDTH - 1 - col)) == 0 {
+                    continue;
+                }
+                let px = x_cursor + col * SCALE;
+                let py = y0 + GLYPH_SPACING + row as u32 * SCALE;
+                for dx in 0..SCALE {
+                    for dy in 0..SCALE {
+                        set_pixel(image, px + dx, py + dy, Rgba([255, 255, 255, 255]));
+                    }
+                }
+            }
+        }
+        x_cursor += char_width;
+    }
+}
+
+fn set_pixel(image: &mut RgbaImage, x: u32, y: u32, color: Rgba<u8>) {
+    if x < image.width() && y < image.height() {
+        image.put_pixel(x, y, color);
+    }
+}
+
+fn blend_pixel(image: &mut RgbaImage, x: u32, y: u32, overlay: Rgba<u8>) {
+    if x >= image.width() || y >= image.height() {
+        return;
+    }
+    let base = *image.get_pixel(x, y);
+    let alpha = overlay[3] as u32;
+    let inv = 255 - alpha;
+    image.put_pixel(
+        x,
+        y,
+        Rgba([
+            ((overlay[0] as u32 * alpha + base[0] as u32 * inv) / 255) as u8,
+            ((overlay[1] as u32 * alpha + base[1] as u32 * inv) / 255) as u8,
+            ((overlay[2] as u32 * alpha + base[2] as u32 * inv) / 255) as u8,
+            255,
+        ]),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn draw_overlay_darkens_the_backing_bar_and_lights_glyph_pixels() {
+        let mut image = RgbaImage::from_pixel(40, 20, Rgba([200, 200, 200, 255]));
+        draw_overlay(&mut image, "1");
+
+        // The backing bar should have darkened the bottom-left corner.
+        assert_eq!(*image.get_pixel(0, 19), Rgba([74, 74, 74, 255]));
+        // '1' lights its middle column in the glyph's top row.
+        assert_eq!(*image.get_pixel(4, 8), Rgba([255, 255, 255, 255]));
+    }
+
+    #[test]
+    fn draw_overlay_does_nothing_for_empty_text() {
+        let mut image = RgbaImage::from_pixel(10, 10, Rgba([1, 2, 3, 255]));
+        draw_overlay(&mut image, "");
+        assert_eq!(*image.get_pixel(0, 9), Rgba([1, 2, 3, 255]));
+    }
+}
@@ -0,0 +1,8 @@
+pub mod clocks;
+pub mod config;
+pub mod db;
+pub mod export;
+pub mod phash;
+pub mod platform;
+pub mod tracker;
+pub mod tui;
@@ -5,12 +5,48 @@ async fn main() -> anyhow::Result<()> {
     dotenv::dotenv().ok();
 
     let config = core::config::Config::load().unwrap_or_default();
-    let db_url = config.database_url;
+    let db_url = config.database_url.clone();
     println!("Using database URL: {}", db_url);
-    let db_pool = db::pool::create_pool(&db_url).await?;
 
-    let processor = WindowEventProcessor::new(db_pool);
+    #[cfg(feature = "crash-handler")]
+    core::platform::win::crash_handler::install(&db_url);
+
+    let db_pool = db::pool::create_pool(&db_url, config.resolved_encryption_key().as_deref()).await?;
+
+    spawn_backup_task(db_pool.clone(), &config);
+
+    let processor = WindowEventProcessor::new(db_pool)
+        .with_idle_threshold(std::time::Duration::from_secs(config.idle_threshold_secs));
     processor.start();
     tokio::signal::ctrl_c().await?;
     Ok(())
 }
+
+/// Spawns the periodic online-backup task: snapshots the live pool on the
+/// configured interval, prunes old snapshots beyond `backup_keep`, and
+/// truncates the WAL afterward. A failed backup only logs, it never aborts
+/// the process, so the capture loop keeps running either way.
+fn spawn_backup_task(db_pool: sqlx::SqlitePool, config: &core::config::Config) {
+    let backup_dir = config.backup_dir.clone();
+    let backup_keep = config.backup_keep;
+    let interval = std::time::Duration::from_secs(config.backup_interval_hours.max(1) * 3600);
+
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            match db::backup::snapshot(&db_pool, &backup_dir).await {
+                Ok(path) => {
+                    println!("Wrote database backup to {}", path.display());
+                    if let Err(e) = db::backup::prune_backups(&backup_dir, backup_keep) {
+                        eprintln!("Failed to prune old backups: {}", e);
+                    }
+                    if let Err(e) = db::backup::checkpoint_truncate(&db_pool).await {
+                        eprintln!("Failed to checkpoint WAL after backup: {}", e);
+                    }
+                }
+                Err(e) => eprintln!("Database backup failed: {}", e),
+            }
+        }
+    });
+}
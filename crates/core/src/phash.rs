@@ -0,0 +1,76 @@
+//! 64-bit perceptual hash (dHash) for screenshot deduplication. The image is
+//! downscaled to 9x8 grayscale, then each of the 8 rows contributes 8 bits:
+//! one per adjacent pixel pair, set when the left pixel is brighter than the
+//! right one. Two captures of a mostly-static window hash only a handful of
+//! bits apart; an actual content change flips many more, so comparing
+//! `hamming_distance` against a small threshold is enough to tell them apart
+//! without ever decoding the full-resolution image twice.
+
+use image::{GenericImageView, ImageBuffer, Rgb, imageops::FilterType};
+
+const HASH_WIDTH: u32 = 9;
+const HASH_HEIGHT: u32 = 8;
+
+/// Computes the dHash of an RGB screenshot, as described above.
+pub fn dhash(image: &ImageBuffer<Rgb<u8>, Vec<u8>>) -> u64 {
+    let small = image::imageops::resize(image, HASH_WIDTH, HASH_HEIGHT, FilterType::Triangle);
+
+    let mut hash = 0u64;
+    let mut bit = 0;
+    for y in 0..HASH_HEIGHT {
+        for x in 0..HASH_WIDTH - 1 {
+            let left = luma(small.get_pixel(x, y));
+            let right = luma(small.get_pixel(x + 1, y));
+            if left > right {
+                hash |= 1 << bit;
+            }
+            bit += 1;
+        }
+    }
+    hash
+}
+
+fn luma(pixel: &Rgb<u8>) -> u32 {
+    let [r, g, b] = pixel.0;
+    // Standard luma weights; integer math is plenty for a brighter/darker comparison.
+    (r as u32 * 299 + g as u32 * 587 + b as u32 * 114) / 1000
+}
+
+/// Number of differing bits between two hashes.
+pub fn hamming_distance(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solid(width: u32, height: u32, color: [u8; 3]) -> ImageBuffer<Rgb<u8>, Vec<u8>> {
+        ImageBuffer::from_fn(width, height, |_, _| Rgb(color))
+    }
+
+    #[test]
+    fn identical_images_hash_identically() {
+        let img = solid(100, 100, [120, 40, 200]);
+        assert_eq!(hamming_distance(dhash(&img), dhash(&img)), 0);
+    }
+
+    #[test]
+    fn a_uniform_color_shift_does_not_change_the_hash() {
+        // A flat image has no left/right brightness difference anywhere, at
+        // any scale, so dimming or brightening it uniformly still hashes to
+        // all-zero bits.
+        let dark = solid(64, 64, [10, 10, 10]);
+        let light = solid(64, 64, [240, 240, 240]);
+        assert_eq!(dhash(&dark), dhash(&light));
+    }
+
+    #[test]
+    fn a_split_image_hashes_far_from_a_solid_one() {
+        let plain = solid(64, 64, [128, 128, 128]);
+        let split = ImageBuffer::from_fn(64, 64, |x, _y| {
+            if x < 32 { Rgb([255, 255, 255]) } else { Rgb([0, 0, 0]) }
+        });
+        assert!(hamming_distance(dhash(&plain), dhash(&split)) > 0);
+    }
+}
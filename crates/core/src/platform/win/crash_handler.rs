@@ -0,0 +1,128 @@
+//! Opt-in crash diagnostics for the native capture process. The window-event
+//! hook and GDI screenshot code spend most of their time in `unsafe` FFI;
+//! if one of those calls faults, this writes a minidump plus a sidecar JSON
+//! describing what the tracker last saw, so a crash can be triaged without
+//! attaching a debugger. Gated behind the `crash-handler` feature so builds
+//! that don't want it pay nothing.
+#![cfg(feature = "crash-handler")]
+
+use serde::Serialize;
+use std::sync::Mutex;
+
+use windows::Win32::Foundation::{BOOL, HANDLE};
+use windows::Win32::Storage::FileSystem::{CreateFileW, FILE_GENERIC_WRITE, FILE_SHARE_NONE};
+use windows::Win32::System::Diagnostics::Debug::{
+    EXCEPTION_POINTERS, SetUnhandledExceptionFilter,
+};
+use windows::Win32::System::Diagnostics::Debug::{
+    MiniDumpWithFullMemoryInfo, MiniDumpWriteDump, MINIDUMP_EXCEPTION_INFORMATION,
+};
+use windows::Win32::System::Threading::GetCurrentProcess;
+use windows::core::PCWSTR;
+
+#[derive(Debug, Clone, Serialize, Default)]
+struct LastWindow {
+    name: String,
+    title: String,
+    path: String,
+}
+
+static LAST_WINDOW: Mutex<Option<LastWindow>> = Mutex::new(None);
+
+#[derive(Serialize)]
+struct CrashMetadata {
+    last_window: Option<LastWindow>,
+    database_url: String,
+    build_version: &'static str,
+}
+
+/// Called from `platform::win::tracker::send_window_info` on every
+/// foreground, restore and title-change event so a crash handler has something to report even though
+/// the fault can happen long after the window that triggered it was seen.
+pub fn record_last_window(name: &str, title: &str, path: &str) {
+    if let Ok(mut slot) = LAST_WINDOW.lock() {
+        *slot = Some(LastWindow {
+            name: name.to_string(),
+            title: title.to_string(),
+            path: path.to_string(),
+        });
+    }
+}
+
+/// Installs a top-level exception filter that writes a `.dmp` + `.extra`
+/// pair into `config_dir/intime/crashes` on an unhandled exception. Call
+/// this once during startup.
+pub fn install(database_url: &str) {
+    let database_url = database_url.to_string();
+    if let Ok(mut slot) = DATABASE_URL.lock() {
+        *slot = database_url;
+    }
+
+    unsafe {
+        SetUnhandledExceptionFilter(Some(crash_handler));
+    }
+}
+
+static DATABASE_URL: Mutex<String> = Mutex::new(String::new());
+
+unsafe extern "system" fn crash_handler(exception_info: *const EXCEPTION_POINTERS) -> i32 {
+    let _ = write_dump(exception_info as *mut EXCEPTION_POINTERS);
+    // EXCEPTION_CONTINUE_SEARCH: let the default OS crash handling run too,
+    // we're only here to capture diagnostics.
+    0
+}
+
+unsafe fn write_dump(exception_info: *mut EXCEPTION_POINTERS) -> Option<()> {
+    let crash_dir = dirs::config_dir()?.join("intime").join("crashes");
+    std::fs::create_dir_all(&crash_dir).ok()?;
+
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .ok()?
+        .as_secs();
+    let dump_path = crash_dir.join(format!("intime_{timestamp}.dmp"));
+    let extra_path = crash_dir.join(format!("intime_{timestamp}.extra"));
+
+    let wide_path: Vec<u16> = dump_path
+        .to_str()?
+        .encode_utf16()
+        .chain(std::iter::once(0))
+        .collect();
+
+    let file = CreateFileW(
+        PCWSTR(wide_path.as_ptr()),
+        FILE_GENERIC_WRITE.0,
+        FILE_SHARE_NONE,
+        None,
+        windows::Win32::Storage::FileSystem::CREATE_ALWAYS,
+        windows::Win32::Storage::FileSystem::FILE_ATTRIBUTE_NORMAL,
+        None,
+    )
+    .ok()?;
+
+    let mut exception_param = MINIDUMP_EXCEPTION_INFORMATION {
+        ThreadId: windows::Win32::System::Threading::GetCurrentThreadId(),
+        ExceptionPointers: exception_info,
+        ClientPointers: BOOL(0),
+    };
+
+    let _ = MiniDumpWriteDump(
+        GetCurrentProcess(),
+        windows::Win32::System::Threading::GetCurrentProcessId(),
+        HANDLE(file.0),
+        MiniDumpWithFullMemoryInfo,
+        Some(&mut exception_param),
+        None,
+        None,
+    );
+
+    let metadata = CrashMetadata {
+        last_window: LAST_WINDOW.lock().ok().and_then(|g| g.clone()),
+        database_url: DATABASE_URL.lock().map(|g| g.clone()).unwrap_or_default(),
+        build_version: env!("CARGO_PKG_VERSION"),
+    };
+    let json = serde_json::to_string_pretty(&metadata).ok()?;
+    std::fs::write(extra_path, json).ok()?;
+
+    Some(())
+}
@@ -0,0 +1,24 @@
+#[cfg(feature = "crash-handler")]
+pub mod crash_handler;
+pub mod presence;
+pub mod process_info;
+pub mod screenshot;
+pub mod tracker;
+
+pub use presence::{IdleWatcherHandle, run_session_watcher, spawn_idle_watcher};
+pub use screenshot::{evict_cached_dib_sections, screenshot_window};
+pub use tracker::set_win_event_hook;
+
+/// Posts `WM_QUIT` to a message-loop thread by id, so its `GetMessageW` loop
+/// returns and the thread exits on its own. `JoinHandle::abort` on a
+/// `spawn_blocking` task wrapping such a loop is a documented no-op — it
+/// detaches the underlying OS thread without interrupting it — so this is
+/// the only way `ProcessorHandle::stop` can actually end the hook and
+/// session-watcher threads rather than leaving them running forever.
+pub fn post_quit_to_thread(thread_id: u32) {
+    use windows::Win32::Foundation::{LPARAM, WPARAM};
+    use windows::Win32::UI::WindowsAndMessaging::{PostThreadMessageW, WM_QUIT};
+    unsafe {
+        let _ = PostThreadMessageW(thread_id, WM_QUIT, WPARAM(0), LPARAM(0));
+    }
+}
@@ -0,0 +1,194 @@
+//! Idle and session-lock detection, feeding the same event channel
+//! `tracker::set_win_event_hook` sends foreground events on. A machine that's
+//! idle or locked shouldn't accrue active time for whatever app happened to
+//! be foreground when the user stepped away.
+use std::time::Duration;
+use tokio::sync::broadcast::Sender;
+
+use windows::Win32::Foundation::{
+    ERROR_CLASS_ALREADY_EXISTS, GetLastError, HWND, LPARAM, LRESULT, WPARAM,
+};
+use windows::Win32::System::RemoteDesktop::{
+    NOTIFY_FOR_THIS_SESSION, WTSRegisterSessionNotification, WTSUnRegisterSessionNotification,
+};
+use windows::Win32::System::SystemInformation::GetTickCount;
+use windows::Win32::UI::Input::KeyboardAndMouse::{GetLastInputInfo, LASTINPUTINFO};
+use windows::Win32::UI::WindowsAndMessaging::{
+    CreateWindowExW, DefWindowProcW, DestroyWindow, DispatchMessageW, GetMessageW, HWND_MESSAGE,
+    MSG, RegisterClassW, TranslateMessage, WINDOW_EX_STYLE, WM_WTSSESSION_CHANGE, WNDCLASSW,
+    WS_OVERLAPPED,
+};
+use windows::core::PCWSTR;
+
+use crate::clocks::{Clocks, RealClocks};
+use crate::tracker::events::WindowEvent;
+
+const WTS_SESSION_LOCK: u32 = 0x7;
+const WTS_SESSION_UNLOCK: u32 = 0x8;
+
+/// Owns the stop signal for [`spawn_idle_watcher`]'s thread. Unlike
+/// `JoinHandle::abort` on an async task, there's no way to forcibly
+/// interrupt a plain OS thread, so shutdown instead asks it to exit its loop
+/// (via `stop_tx`) and waits for that to happen.
+pub struct IdleWatcherHandle {
+    stop_tx: std::sync::mpsc::Sender<()>,
+    thread: std::thread::JoinHandle<()>,
+}
+
+impl IdleWatcherHandle {
+    /// Signals the watcher thread to exit its poll loop and joins it, so the
+    /// caller knows `GetLastInputInfo` polling has actually stopped before
+    /// starting a replacement.
+    pub fn stop(self) {
+        let _ = self.stop_tx.send(());
+        let _ = self.thread.join();
+    }
+}
+
+/// Polls `GetLastInputInfo` on its own thread and sends a
+/// [`WindowEvent::IdleTransition`] across `sender` each time idle duration
+/// crosses `idle_threshold`, in either direction. Returns a handle so a
+/// caller that reconfigures at runtime can stop this watcher before starting
+/// its replacement instead of leaking the thread.
+pub fn spawn_idle_watcher(sender: Sender<WindowEvent>, idle_threshold: Duration) -> IdleWatcherHandle {
+    let (stop_tx, stop_rx) = std::sync::mpsc::channel::<()>();
+
+    let thread = std::thread::spawn(move || {
+        let mut is_idle = false;
+        loop {
+            match stop_rx.recv_timeout(Duration::from_secs(1)) {
+                Ok(()) => return,
+                Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => return,
+                Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {}
+            }
+
+            let should_be_idle = current_idle_duration() >= idle_threshold;
+            if should_be_idle != is_idle {
+                is_idle = should_be_idle;
+                let event = WindowEvent::IdleTransition {
+                    idle: is_idle,
+                    timestamp_sec: RealClocks.now_unix_secs(),
+                };
+                if sender.send(event).is_err() {
+                    return; // Receiver gone, processor has shut down.
+                }
+            }
+        }
+    });
+
+    IdleWatcherHandle { stop_tx, thread }
+}
+
+fn current_idle_duration() -> Duration {
+    let mut info = LASTINPUTINFO {
+        cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32,
+        dwTime: 0,
+    };
+
+    unsafe {
+        if GetLastInputInfo(&mut info).as_bool() {
+            Duration::from_millis(GetTickCount().saturating_sub(info.dwTime) as u64)
+        } else {
+            Duration::ZERO
+        }
+    }
+}
+
+thread_local! {
+    static SESSION_CHANGE_SENDER: std::cell::RefCell<Option<Sender<WindowEvent>>> =
+        std::cell::RefCell::new(None);
+}
+
+/// Creates a hidden message-only window, registers it for
+/// `WM_WTSSESSION_CHANGE` notifications via `WTSRegisterSessionNotification`,
+/// and pumps its own `GetMessageW` loop on the calling thread — mirroring
+/// `tracker::run_message_loop`'s hook thread, just for a different message
+/// source. Intended to be run inside its own `spawn_blocking` task.
+///
+/// Reports this thread's id on `thread_id_tx` once the window is live, so a
+/// caller holding the other end (`ProcessorHandle`) can later post `WM_QUIT`
+/// to unblock `GetMessageW` and let this function return on its own —
+/// `JoinHandle::abort` cannot interrupt a thread blocked in a Win32 message
+/// loop.
+pub fn run_session_watcher(sender: Sender<WindowEvent>, thread_id_tx: std::sync::mpsc::Sender<u32>) {
+    use windows::Win32::System::Threading::GetCurrentThreadId;
+
+    SESSION_CHANGE_SENDER.with(|cell| {
+        *cell.borrow_mut() = Some(sender);
+    });
+
+    let class_name: Vec<u16> = "IntimePresenceWatcher\0".encode_utf16().collect();
+
+    unsafe {
+        let class = WNDCLASSW {
+            lpfnWndProc: Some(session_watcher_wndproc),
+            lpszClassName: PCWSTR(class_name.as_ptr()),
+            ..Default::default()
+        };
+        // `WindowEventProcessor` spawns this watcher fresh on every restart
+        // (e.g. each config save), so the class is typically already
+        // registered from a previous run — that's not a failure, the window
+        // below still creates fine against it. Only bail on a real
+        // registration error.
+        if RegisterClassW(&class) == 0 && GetLastError() != ERROR_CLASS_ALREADY_EXISTS {
+            return;
+        }
+
+        let Ok(hwnd) = CreateWindowExW(
+            WINDOW_EX_STYLE::default(),
+            PCWSTR(class_name.as_ptr()),
+            PCWSTR(class_name.as_ptr()),
+            WS_OVERLAPPED,
+            0,
+            0,
+            0,
+            0,
+            Some(HWND_MESSAGE),
+            None,
+            None,
+            None,
+        ) else {
+            return;
+        };
+
+        let _ = WTSRegisterSessionNotification(hwnd, NOTIFY_FOR_THIS_SESSION);
+        let _ = thread_id_tx.send(GetCurrentThreadId());
+
+        let mut msg = std::mem::zeroed::<MSG>();
+        while GetMessageW(&mut msg, None, 0, 0).into() {
+            let _ = TranslateMessage(&msg);
+            DispatchMessageW(&msg);
+        }
+
+        let _ = WTSUnRegisterSessionNotification(hwnd);
+        let _ = DestroyWindow(hwnd);
+    }
+}
+
+unsafe extern "system" fn session_watcher_wndproc(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    if msg == WM_WTSSESSION_CHANGE {
+        let locked = match wparam.0 as u32 {
+            WTS_SESSION_LOCK => Some(true),
+            WTS_SESSION_UNLOCK => Some(false),
+            _ => None,
+        };
+        if let Some(locked) = locked {
+            SESSION_CHANGE_SENDER.with(|cell| {
+                if let Some(sender) = &*cell.borrow() {
+                    let _ = sender.send(WindowEvent::SessionLock {
+                        locked,
+                        timestamp_sec: RealClocks.now_unix_secs(),
+                    });
+                }
+            });
+        }
+        return LRESULT(0);
+    }
+
+    DefWindowProcW(hwnd, msg, wparam, lparam)
+}
@@ -0,0 +1,123 @@
+//! Richer process identity: the command line and parent process name for a
+//! window's owning process, read by walking its PEB. This lets callers tell
+//! apart workloads that otherwise collapse to the same executable basename
+//! (Electron apps, `javaw.exe`, `python.exe`, multiple browser profiles).
+use std::mem::MaybeUninit;
+use windows::Win32::Foundation::{HANDLE, UNICODE_STRING};
+use windows::Win32::System::Diagnostics::Debug::ReadProcessMemory;
+use windows::Win32::System::Threading::{
+    NtQueryInformationProcess, OpenProcess, PROCESSINFOCLASS, PROCESS_BASIC_INFORMATION,
+    PROCESS_QUERY_INFORMATION, PROCESS_VM_READ,
+};
+
+/// Layout of the fields we need from `RTL_USER_PROCESS_PARAMETERS`. The
+/// `windows` crate doesn't expose this struct (it's undocumented), so we
+/// only read the `CommandLine` field at its known offset.
+#[repr(C)]
+struct ProcessParametersCommandLine {
+    _reserved: [u8; 112],
+    command_line: UNICODE_STRING,
+}
+
+/// Process identity beyond the executable path: its command line (argv as a
+/// single string, Windows-style) and its parent's process id, when they
+/// could be read.
+#[derive(Debug, Clone, Default)]
+pub struct ProcessIdentity {
+    pub command_line: Option<String>,
+    pub parent_pid: Option<u32>,
+}
+
+/// Reads `pid`'s command line and parent pid by locating its PEB via
+/// `NtQueryInformationProcess(ProcessBasicInformation)` and following
+/// `Peb->ProcessParameters->CommandLine` with `ReadProcessMemory`.
+///
+/// Degrades gracefully (returns a mostly-`None` `ProcessIdentity`) when the
+/// target process is protected or elevated and the read fails, rather than
+/// surfacing an error — callers already treat app identity as best-effort.
+pub fn read_process_identity(pid: u32) -> ProcessIdentity {
+    read_process_identity_inner(pid).unwrap_or_default()
+}
+
+fn read_process_identity_inner(pid: u32) -> Option<ProcessIdentity> {
+    unsafe {
+        let process_handle =
+            OpenProcess(PROCESS_QUERY_INFORMATION | PROCESS_VM_READ, false, pid).ok()?;
+        let _guard = scopeguard::guard(process_handle, |h| {
+            let _ = windows::Win32::Foundation::CloseHandle(h);
+        });
+
+        let mut basic_info = MaybeUninit::<PROCESS_BASIC_INFORMATION>::zeroed();
+        let mut returned_len = 0u32;
+        let status = NtQueryInformationProcess(
+            process_handle,
+            PROCESSINFOCLASS(0), // ProcessBasicInformation
+            basic_info.as_mut_ptr() as *mut _,
+            std::mem::size_of::<PROCESS_BASIC_INFORMATION>() as u32,
+            &mut returned_len,
+        );
+        if status.is_err() {
+            return None;
+        }
+        let basic_info = basic_info.assume_init();
+
+        let parent_pid = Some(basic_info.InheritedFromUniqueProcessId as u32);
+
+        let command_line = read_command_line(process_handle, &basic_info);
+
+        Some(ProcessIdentity {
+            command_line,
+            parent_pid,
+        })
+    }
+}
+
+unsafe fn read_command_line(
+    process_handle: HANDLE,
+    basic_info: &PROCESS_BASIC_INFORMATION,
+) -> Option<String> {
+    // PEB.ProcessParameters sits right after a fixed block of reserved
+    // fields; rather than modeling the whole PEB we read just the pointer
+    // we need at its known offset (0x20 on x64).
+    const PEB_PROCESS_PARAMETERS_OFFSET: usize = 0x20;
+
+    let peb_addr = basic_info.PebBaseAddress as *const u8;
+    let mut process_parameters_ptr: usize = 0;
+    let mut bytes_read = 0usize;
+    ReadProcessMemory(
+        process_handle,
+        peb_addr.add(PEB_PROCESS_PARAMETERS_OFFSET) as *const _,
+        &mut process_parameters_ptr as *mut usize as *mut _,
+        std::mem::size_of::<usize>(),
+        Some(&mut bytes_read),
+    )
+    .ok()?;
+
+    let mut params = MaybeUninit::<ProcessParametersCommandLine>::zeroed();
+    ReadProcessMemory(
+        process_handle,
+        process_parameters_ptr as *const _,
+        params.as_mut_ptr() as *mut _,
+        std::mem::size_of::<ProcessParametersCommandLine>(),
+        Some(&mut bytes_read),
+    )
+    .ok()?;
+    let params = params.assume_init();
+
+    let command_line_len = params.command_line.Length as usize;
+    if command_line_len == 0 {
+        return None;
+    }
+
+    let mut buffer = vec![0u16; command_line_len / 2];
+    ReadProcessMemory(
+        process_handle,
+        params.command_line.Buffer.0 as *const _,
+        buffer.as_mut_ptr() as *mut _,
+        command_line_len,
+        Some(&mut bytes_read),
+    )
+    .ok()?;
+
+    Some(String::from_utf16_lossy(&buffer))
+}
@@ -1,6 +1,8 @@
 use image::GenericImageView as _;
 use image::ImageBuffer;
 use image::Rgb;
+use std::collections::HashMap;
+use std::sync::Mutex;
 use windows::Win32::Graphics::Gdi::HGDIOBJ;
 use windows::Win32::Storage::Xps::PrintWindow;
 use windows::Win32::UI::WindowsAndMessaging::GetWindowRect;
@@ -8,29 +10,102 @@ use windows::Win32::UI::WindowsAndMessaging::PW_RENDERFULLCONTENT;
 use windows::Win32::{
     Foundation::{HWND, RECT},
     Graphics::Gdi::{
-        BI_RGB, BITMAPINFO, BITMAPINFOHEADER, BitBlt, CreateCompatibleBitmap, CreateCompatibleDC,
-        DIB_RGB_COLORS, DeleteDC, DeleteObject, GetDC, GetDIBits, HBITMAP, HDC, ReleaseDC, SRCCOPY,
+        BI_RGB, BITMAPINFO, BITMAPINFOHEADER, BitBlt, CreateCompatibleDC, CreateDIBSection,
+        DIB_RGB_COLORS, DeleteDC, DeleteObject, GetDC, HBITMAP, HDC, ReleaseDC, SRCCOPY,
         SelectObject,
     },
 };
 
+/// A DIB section keyed by `(hwnd, width, height)`: `PrintWindow`/`BitBlt`
+/// render straight into `pixels`, so repeated captures of the same
+/// foreground window (scheduled every few seconds by
+/// `schedule_screenshot`) reuse the allocation instead of creating and
+/// tearing down a bitmap every interval.
+struct DibSection {
+    hbitmap: HBITMAP,
+    /// Raw pointer into the section's pixel memory, valid for as long as
+    /// `hbitmap` lives. `width * height * 4` BGRA bytes, top-down.
+    pixels: *mut u8,
+}
+
+// The pointer is only ever touched while `DIB_CACHE`'s mutex is held, so it's
+// safe to hand the section across the thread pool `spawn_blocking` may use.
+unsafe impl Send for DibSection {}
+
+static DIB_CACHE: Mutex<Option<HashMap<(isize, i32, i32), DibSection>>> = Mutex::new(None);
+
 pub fn screenshot_window(hwnd: HWND) -> Option<ImageBuffer<Rgb<u8>, Vec<u8>>> {
     unsafe {
         let hdc_window = GetDC(Some(hwnd));
         let (width, height) = get_window_size(hwnd)?;
         let hdc_mem = CreateCompatibleDC(Some(hdc_window));
-        let hbitmap = create_bitmap(hwnd, hdc_window, hdc_mem, width, height)?;
-        let buffer = extract_bitmap_data(hdc_mem, hbitmap, width, height)?;
-        let img = construct_image(width, height, buffer)?;
-        let cleaned_img = remove_black_borders(&img);
 
-        // Cleanup
-        let _ = DeleteObject(hbitmap.into());
+        let img = with_cached_dib_section(hwnd, width, height, |hbitmap, pixels| {
+            SelectObject(hdc_mem, HGDIOBJ(hbitmap.0));
+            if !render_into(hwnd, hdc_window, hdc_mem, width, height) {
+                return None;
+            }
+            let buffer = std::slice::from_raw_parts(pixels, (width * height * 4) as usize);
+            let img = construct_image(width, height, buffer)?;
+            Some(remove_black_borders(&img))
+        });
+
         let _ = DeleteDC(hdc_mem);
         ReleaseDC(Some(hwnd), hdc_window);
 
-        Some(cleaned_img)
+        img
+    }
+}
+
+/// Evicts and frees every cached DIB section for `hwnd`. Call this when a
+/// window is destroyed (`EVENT_OBJECT_DESTROY`) so the cache doesn't hold
+/// onto GDI resources for windows that no longer exist.
+pub fn evict_cached_dib_sections(hwnd: HWND) {
+    let mut cache = DIB_CACHE.lock().unwrap();
+    if let Some(cache) = cache.as_mut() {
+        cache.retain(|(cached_hwnd, _, _), section| {
+            let keep = *cached_hwnd != hwnd.0 as isize;
+            if !keep {
+                unsafe {
+                    let _ = DeleteObject(section.hbitmap.into());
+                }
+            }
+            keep
+        });
+    }
+}
+
+unsafe fn with_cached_dib_section<T>(
+    hwnd: HWND,
+    width: i32,
+    height: i32,
+    f: impl FnOnce(HBITMAP, *mut u8) -> Option<T>,
+) -> Option<T> {
+    let key = (hwnd.0 as isize, width, height);
+    let mut cache = DIB_CACHE.lock().unwrap();
+    let cache = cache.get_or_insert_with(HashMap::new);
+
+    if !cache.contains_key(&key) {
+        // A resize (maximize/restore, snap, manual drag) doesn't destroy the
+        // window, so nothing else evicts the old size for this hwnd — do it
+        // here, or every size a window has ever been leaks its HBITMAP for
+        // the life of the process.
+        cache.retain(|(cached_hwnd, _, _), section| {
+            let keep = *cached_hwnd != key.0;
+            if !keep {
+                unsafe {
+                    let _ = DeleteObject(section.hbitmap.into());
+                }
+            }
+            keep
+        });
+
+        let (hbitmap, pixels) = create_dib_section(width, height)?;
+        cache.insert(key, DibSection { hbitmap, pixels });
     }
+
+    let section = cache.get(&key)?;
+    f(section.hbitmap, section.pixels)
 }
 
 fn get_window_size(hwnd: HWND) -> Option<(i32, i32)> {
@@ -46,63 +121,16 @@ fn get_window_size(hwnd: HWND) -> Option<(i32, i32)> {
     }
 }
 
-fn create_bitmap(
-    hwnd: HWND,
-    hdc_window: HDC,
-    hdc_mem: HDC,
-    width: i32,
-    height: i32,
-) -> Option<HBITMAP> {
+/// Creates a top-down, 32bpp DIB section and hands back both the `HBITMAP`
+/// and a raw pointer to its backing pixel memory, so callers can read
+/// captured pixels directly without a `GetDIBits` round-trip.
+fn create_dib_section(width: i32, height: i32) -> Option<(HBITMAP, *mut u8)> {
     unsafe {
-        let hbitmap = CreateCompatibleBitmap(hdc_window, width, height);
-        if hbitmap.0 == std::ptr::null_mut() {
-            println!("Failed to create compatible bitmap");
-            return None;
-        }
-
-        let _old_bitmap = SelectObject(hdc_mem, HGDIOBJ(hbitmap.0));
-
-        // Try PrintWindow first (better for modern apps)
-        let print_success = PrintWindow(
-            hwnd,
-            hdc_mem,
-            windows::Win32::Storage::Xps::PRINT_WINDOW_FLAGS(PW_RENDERFULLCONTENT),
-        );
-
-        if !print_success.as_bool() {
-            println!("PrintWindow failed, trying BitBlt...");
-            let success = BitBlt(
-                hdc_mem,
-                0,
-                0,
-                width,
-                height,
-                Some(hdc_window),
-                0,
-                0,
-                SRCCOPY,
-            );
-
-            if success.is_ok() {
-                println!("BitBlt succeeded");
-            } else {
-                println!("BitBlt failed");
-                let _ = DeleteObject(hbitmap.into());
-                return None;
-            }
-        }
-
-        Some(hbitmap)
-    }
-}
-
-fn extract_bitmap_data(hdc_mem: HDC, hbitmap: HBITMAP, width: i32, height: i32) -> Option<Vec<u8>> {
-    unsafe {
-        let mut bmi = BITMAPINFO {
+        let bmi = BITMAPINFO {
             bmiHeader: BITMAPINFOHEADER {
                 biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
                 biWidth: width,
-                biHeight: -height, // top-down
+                biHeight: -height, // negative => top-down rows
                 biPlanes: 1,
                 biBitCount: 32,
                 biCompression: BI_RGB.0,
@@ -110,35 +138,64 @@ fn extract_bitmap_data(hdc_mem: HDC, hbitmap: HBITMAP, width: i32, height: i32)
             },
             ..Default::default()
         };
-        let mut buffer = vec![0u8; (width * height * 4) as usize];
-        let res = GetDIBits(
-            hdc_mem,
-            hbitmap,
-            0,
-            height as u32,
-            Some(buffer.as_mut_ptr() as *mut _),
-            &mut bmi,
+
+        let screen_dc = GetDC(None);
+        let mut ppv_bits: *mut std::ffi::c_void = std::ptr::null_mut();
+        let hbitmap = CreateDIBSection(
+            Some(screen_dc),
+            &bmi,
             DIB_RGB_COLORS,
-        );
-        if res == 0 { None } else { Some(buffer) }
+            &mut ppv_bits,
+            None,
+            0,
+        )
+        .ok()?;
+        ReleaseDC(None, screen_dc);
+
+        if hbitmap.0 == std::ptr::null_mut() || ppv_bits.is_null() {
+            return None;
+        }
+
+        Some((hbitmap, ppv_bits as *mut u8))
     }
 }
-fn construct_image(
-    width: i32,
-    height: i32,
-    buffer: Vec<u8>,
-) -> Option<ImageBuffer<Rgb<u8>, Vec<u8>>> {
-    // Convert BGRA to RGBA
-    let mut rgba_buffer = Vec::with_capacity(buffer.len());
+
+unsafe fn render_into(hwnd: HWND, hdc_window: HDC, hdc_mem: HDC, width: i32, height: i32) -> bool {
+    let print_success = PrintWindow(
+        hwnd,
+        hdc_mem,
+        windows::Win32::Storage::Xps::PRINT_WINDOW_FLAGS(PW_RENDERFULLCONTENT),
+    );
+
+    if print_success.as_bool() {
+        return true;
+    }
+
+    BitBlt(
+        hdc_mem,
+        0,
+        0,
+        width,
+        height,
+        Some(hdc_window),
+        0,
+        0,
+        SRCCOPY,
+    )
+    .is_ok()
+}
+
+fn construct_image(width: i32, height: i32, buffer: &[u8]) -> Option<ImageBuffer<Rgb<u8>, Vec<u8>>> {
+    // Convert BGRA (as written into the DIB section) to RGB, dropping alpha.
+    let mut rgb_buffer = Vec::with_capacity(buffer.len() / 4 * 3);
 
     for chunk in buffer.chunks_exact(4) {
-        rgba_buffer.push(chunk[2]); // R (was B)
-        rgba_buffer.push(chunk[1]); // G (stays G)
-        rgba_buffer.push(chunk[0]); // B (was R)
-        // rgba_buffer.push(chunk[3]); // A (stays A)
+        rgb_buffer.push(chunk[2]); // R (was B)
+        rgb_buffer.push(chunk[1]); // G (stays G)
+        rgb_buffer.push(chunk[0]); // B (was R)
     }
 
-    ImageBuffer::<Rgb<u8>, Vec<u8>>::from_raw(width as u32, height as u32, rgba_buffer)
+    ImageBuffer::<Rgb<u8>, Vec<u8>>::from_raw(width as u32, height as u32, rgb_buffer)
 }
 
 fn remove_black_borders(img: &ImageBuffer<Rgb<u8>, Vec<u8>>) -> ImageBuffer<Rgb<u8>, Vec<u8>> {
@@ -148,15 +205,8 @@ fn remove_black_borders(img: &ImageBuffer<Rgb<u8>, Vec<u8>>) -> ImageBuffer<Rgb<
     let mut left = 0;
     let mut right = width;
 
-    // Helper to check if a pixel is pure black
     let is_black = |p: &Rgb<u8>| p.0[0] == 0 && p.0[1] == 0 && p.0[2] == 0;
 
-    // 4 loops are faster due to the nature of the search,
-    // we only have outlines to check.
-
-    // 'loop_name syntax names the loop so we can break
-    // out of it in the inside loop.
-
     'outer_top: for y in 0..height {
         for x in 0..width {
             if !is_black(&img.get_pixel(x, y)) {
@@ -169,7 +219,7 @@ fn remove_black_borders(img: &ImageBuffer<Rgb<u8>, Vec<u8>>) -> ImageBuffer<Rgb<
     'outer_bottom: for y in (0..height).rev() {
         for x in 0..width {
             if !is_black(&img.get_pixel(x, y)) {
-                bottom = y + 1; // +1 since it's exclusive
+                bottom = y + 1;
                 break 'outer_bottom;
             }
         }
@@ -187,12 +237,11 @@ fn remove_black_borders(img: &ImageBuffer<Rgb<u8>, Vec<u8>>) -> ImageBuffer<Rgb<
     'outer_right: for x in (0..width).rev() {
         for y in top..bottom {
             if !is_black(&img.get_pixel(x, y)) {
-                right = x + 1; // +1 since it's exclusive
+                right = x + 1;
                 break 'outer_right;
             }
         }
     }
 
-    // Crop the image to the detected bounds
     img.view(left, top, right - left, bottom - top).to_image()
 }
@@ -0,0 +1,311 @@
+use std::ffi::OsString;
+use std::os::windows::ffi::OsStringExt;
+use tokio::sync::broadcast::Sender;
+
+use windows::Win32::Foundation::CloseHandle;
+use windows::Win32::Foundation::HANDLE;
+use windows::Win32::Foundation::HWND;
+use windows::Win32::Foundation::MAX_PATH;
+use windows::Win32::System::Threading::PROCESS_VM_READ;
+use windows::Win32::System::Threading::{
+    OpenProcess, PROCESS_NAME_FORMAT, PROCESS_QUERY_INFORMATION, QueryFullProcessImageNameW,
+};
+use windows::Win32::UI::Accessibility::HWINEVENTHOOK;
+use windows::Win32::UI::Accessibility::SetWinEventHook;
+use windows::Win32::UI::WindowsAndMessaging::EVENT_OBJECT_DESTROY;
+use windows::Win32::UI::WindowsAndMessaging::EVENT_OBJECT_NAMECHANGE;
+use windows::Win32::UI::WindowsAndMessaging::EVENT_SYSTEM_FOREGROUND;
+use windows::Win32::UI::WindowsAndMessaging::EVENT_SYSTEM_MINIMIZEEND;
+use windows::Win32::UI::WindowsAndMessaging::EVENT_SYSTEM_MINIMIZESTART;
+use windows::Win32::UI::WindowsAndMessaging::GWL_EXSTYLE;
+use windows::Win32::UI::WindowsAndMessaging::GetWindowLongW;
+use windows::Win32::UI::WindowsAndMessaging::GetWindowThreadProcessId;
+use windows::Win32::UI::WindowsAndMessaging::IsWindowVisible;
+use windows::Win32::UI::WindowsAndMessaging::OBJID_CLIENT;
+use windows::Win32::UI::WindowsAndMessaging::OBJID_WINDOW;
+use windows::Win32::UI::WindowsAndMessaging::WINEVENT_OUTOFCONTEXT;
+use windows::Win32::UI::WindowsAndMessaging::WS_EX_TOOLWINDOW;
+use windows::Win32::UI::WindowsAndMessaging::{GetForegroundWindow, GetWindowTextW};
+use windows::core::PWSTR;
+
+use crate::clocks::{Clocks, RealClocks};
+use crate::tracker::events::{WindowEvent, WindowInfo};
+
+#[allow(dead_code)]
+fn get_active_window() -> Option<HWND> {
+    unsafe {
+        let hwnd = GetForegroundWindow();
+        if hwnd.0 == std::ptr::null_mut() {
+            None
+        } else {
+            Some(hwnd)
+        }
+    }
+}
+
+fn get_window_title(hwnd: HWND) -> Option<String> {
+    let mut buffer: [u16; MAX_PATH as usize] = [0; MAX_PATH as usize];
+    let length = unsafe { GetWindowTextW(hwnd, &mut buffer) };
+
+    if length > 0 {
+        Some(String::from_utf16_lossy(&buffer[..length as usize]))
+    } else {
+        None
+    }
+}
+
+fn get_process_id(hwnd: HWND) -> Option<u32> {
+    let mut process_id: u32 = 0;
+
+    unsafe {
+        GetWindowThreadProcessId(hwnd, Some(&mut process_id));
+    }
+
+    if process_id != 0 {
+        Some(process_id)
+    } else {
+        None
+    }
+}
+
+fn get_process_handle(process_id: u32) -> Option<HANDLE> {
+    unsafe {
+        let process_handle = OpenProcess(
+            PROCESS_QUERY_INFORMATION | PROCESS_VM_READ,
+            false,
+            process_id,
+        )
+        .ok()?;
+
+        if process_handle.is_invalid() {
+            None
+        } else {
+            Some(process_handle)
+        }
+    }
+}
+
+fn get_app_path(hwnd: HWND) -> Option<String> {
+    let process_id = get_process_id(hwnd)?;
+    let process_handle = get_process_handle(process_id)?;
+    let _handle_closer = scopeguard::guard(process_handle, |h| {
+        unsafe { CloseHandle(h) }.ok();
+    });
+
+    get_app_image_path(process_handle)
+}
+
+/// Resolves an already-open process handle to its executable's full path.
+/// Split out of [`get_app_path`] so a parent process handle (which has no
+/// associated `HWND`) can be resolved the same way.
+fn get_app_image_path(process_handle: HANDLE) -> Option<String> {
+    unsafe {
+        let mut buffer: [u16; MAX_PATH as usize] = [0; MAX_PATH as usize];
+        let mut size: u32 = buffer.len() as u32;
+
+        let success = QueryFullProcessImageNameW(
+            process_handle,
+            PROCESS_NAME_FORMAT::default(),
+            PWSTR(buffer.as_mut_ptr()),
+            &mut size,
+        );
+
+        if success.is_ok() {
+            let path_u16: &[u16] = &buffer[0..size as usize];
+            let os_string = OsString::from_wide(path_u16);
+            let full_path = os_string.to_string_lossy().into_owned();
+            Some(full_path)
+        } else {
+            None
+        }
+    }
+}
+
+/// Resolves a parent process id to its executable's basename. The parent may
+/// have already exited or be inaccessible, in which case this degrades to
+/// `None` rather than surfacing an error, matching how the rest of app
+/// identity is treated as best-effort.
+fn get_parent_app_name(parent_pid: u32) -> Option<String> {
+    let process_handle = get_process_handle(parent_pid)?;
+    let _handle_closer = scopeguard::guard(process_handle, |h| {
+        unsafe { CloseHandle(h) }.ok();
+    });
+
+    get_app_image_path(process_handle).and_then(get_app_name_from_path)
+}
+
+fn get_app_name_from_path(full_path: String) -> Option<String> {
+    std::path::Path::new(&full_path)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .map(|s| s.to_string())
+}
+
+fn now_unix_secs() -> i64 {
+    RealClocks.now_unix_secs()
+}
+
+thread_local! {
+    static WINDOW_CHANGE_SENDER: std::cell::RefCell<Option<Sender<WindowEvent>>> =
+        std::cell::RefCell::new(None);
+}
+
+pub fn set_win_event_hook(
+    sender: Sender<WindowEvent>,
+) -> Result<HWINEVENTHOOK, windows::core::Error> {
+    WINDOW_CHANGE_SENDER.with(|cell| {
+        *cell.borrow_mut() = Some(sender);
+    });
+
+    let hook = unsafe {
+        SetWinEventHook(
+            EVENT_SYSTEM_FOREGROUND,
+            EVENT_OBJECT_NAMECHANGE, // Range also spans minimize/restore/destroy.
+            None,
+            Some(win_event_hook_callback),
+            0,
+            0,
+            WINEVENT_OUTOFCONTEXT,
+        )
+    };
+
+    Ok(hook)
+}
+
+unsafe extern "system" fn win_event_hook_callback(
+    _hook_handle: HWINEVENTHOOK,
+    event_id: u32,
+    window_handle: HWND,
+    object_id: i32,
+    _child_id: i32,
+    _thread_id: u32,
+    _timestamp: u32,
+) {
+    if window_handle.0 == std::ptr::null_mut() {
+        return;
+    }
+
+    let event_info: Option<WindowEvent> = match event_id {
+        // The window may already be invisible by the time DESTROY fires, so
+        // unlike the other variants this doesn't gate on `is_visible_and_valid`.
+        EVENT_OBJECT_DESTROY => {
+            if object_id != OBJID_WINDOW.0 {
+                return;
+            }
+            Some(WindowEvent::Destroyed {
+                hwnd: window_handle.0 as isize,
+                timestamp_sec: now_unix_secs(),
+            })
+        }
+        EVENT_SYSTEM_MINIMIZESTART => {
+            if !is_visible_and_valid(window_handle, object_id) {
+                return;
+            }
+            Some(WindowEvent::Minimized {
+                hwnd: window_handle.0 as isize,
+                timestamp_sec: now_unix_secs(),
+            })
+        }
+        EVENT_SYSTEM_FOREGROUND | EVENT_SYSTEM_MINIMIZEEND | EVENT_OBJECT_NAMECHANGE => {
+            if !is_visible_and_valid(window_handle, object_id)
+                || !is_interesting_window(window_handle)
+            {
+                return;
+            }
+            gather_window_info(window_handle, event_id)
+        }
+        _ => None,
+    };
+
+    if let Some(event) = event_info {
+        send_window_info(event);
+    }
+}
+
+fn gather_window_info(window_handle: HWND, event_id: u32) -> Option<WindowEvent> {
+    let app_path = get_app_path(window_handle);
+    let app_name = app_path
+        .as_ref()
+        .and_then(|path| get_app_name_from_path(path.clone()));
+    let app_title = get_window_title(window_handle);
+    if app_path.is_none() || app_name.is_none() || app_title.is_none() {
+        return None;
+    }
+
+    let identity = get_process_id(window_handle)
+        .map(super::process_info::read_process_identity)
+        .unwrap_or_default();
+    let parent_name = identity.parent_pid.and_then(get_parent_app_name);
+
+    let window = WindowInfo {
+        name: app_name.unwrap(),
+        title: app_title.unwrap(),
+        path: app_path.unwrap(),
+        command_line: identity.command_line,
+        parent_name,
+    };
+    let hwnd = window_handle.0 as isize;
+    let timestamp_sec = now_unix_secs();
+
+    Some(match event_id {
+        EVENT_SYSTEM_MINIMIZEEND => WindowEvent::Restored {
+            hwnd,
+            window,
+            timestamp_sec,
+        },
+        EVENT_OBJECT_NAMECHANGE => WindowEvent::TitleChanged {
+            hwnd,
+            window,
+            timestamp_sec,
+        },
+        _ => WindowEvent::Foreground {
+            hwnd,
+            window,
+            timestamp_sec,
+        },
+    })
+}
+
+fn send_window_info(info: WindowEvent) {
+    #[cfg(feature = "crash-handler")]
+    if let WindowEvent::Foreground { window, .. }
+    | WindowEvent::Restored { window, .. }
+    | WindowEvent::TitleChanged { window, .. } = &info
+    {
+        super::crash_handler::record_last_window(&window.name, &window.title, &window.path);
+    }
+
+    WINDOW_CHANGE_SENDER.with(|cell| {
+        if let Some(sender) = &*cell.borrow() {
+            let _ = sender.send(info);
+        }
+    });
+}
+
+fn is_visible_and_valid(window_handle: HWND, object_id: i32) -> bool {
+    if window_handle.0 == std::ptr::null_mut() {
+        return false;
+    }
+
+    let is_visible = unsafe { IsWindowVisible(window_handle) }.as_bool();
+    let is_valid_object = object_id == OBJID_WINDOW.0 || object_id == OBJID_CLIENT.0;
+    is_visible && is_valid_object
+}
+
+fn is_interesting_window(window_handle: HWND) -> bool {
+    unsafe {
+        let ex_style = GetWindowLongW(window_handle, GWL_EXSTYLE);
+
+        if (ex_style & WS_EX_TOOLWINDOW.0 as i32) != 0 {
+            return false;
+        }
+
+        if let Some(title) = get_window_title(window_handle) {
+            if title.is_empty() {
+                return false;
+            }
+        }
+    }
+
+    true
+}
@@ -0,0 +1,185 @@
+use std::fmt::{Debug, Display};
+
+use serde::Serialize;
+use windows::Win32::UI::WindowsAndMessaging::{
+    EVENT_OBJECT_DESTROY, EVENT_OBJECT_NAMECHANGE, EVENT_SYSTEM_FOREGROUND,
+    EVENT_SYSTEM_MINIMIZEEND, EVENT_SYSTEM_MINIMIZESTART,
+};
+
+#[derive(Clone, Copy)]
+pub struct WindowEventType {
+    pub event_code: u32,
+}
+
+impl WindowEventType {
+    pub fn new(event_code: u32) -> Self {
+        WindowEventType { event_code }
+    }
+}
+
+/// App-defined event codes, namespaced away from real `WINEVENT_*`/`WM_*`
+/// constants (which top out well below this range) so presence transitions
+/// can share `WindowEventType`/the foreground-event channel without risking
+/// a collision.
+pub const EVENT_IDLE_ENTERED: u32 = 99_001;
+pub const EVENT_IDLE_EXITED: u32 = 99_002;
+pub const EVENT_SESSION_LOCKED: u32 = 99_003;
+pub const EVENT_SESSION_UNLOCKED: u32 = 99_004;
+pub const EVENT_MANUAL_PAUSE: u32 = 99_005;
+pub const EVENT_MANUAL_RESUME: u32 = 99_006;
+/// Synthetic boundary row written by `WindowEventProcessor` the instant it
+/// becomes suspended (idle, locked, minimized or paused), so the open
+/// interval for the previously-foreground app ends there instead of at
+/// whatever event happens to come next — which could be arbitrarily far in
+/// the future and get time-accounted as if the app stayed active the whole
+/// time it didn't.
+pub const EVENT_SUSPENDED: u32 = 99_007;
+
+impl Debug for WindowEventType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.event_code {
+            32780u32 => write!(f, "EVENT_SYSTEM_NAMECHANGE"),
+            3u32 => write!(f, "EVENT_SYSTEM_FOREGROUND"),
+            32768u32 => write!(f, "EVENT_OBJECT_CREATE"),
+            32769u32 => write!(f, "EVENT_OBJECT_DESTROY"),
+            32771u32 => write!(f, "EVENT_OBJECT_HIDE"),
+            23u32 => write!(f, "EVENT_SYSTEM_MINIMIZEEND"),
+            22u32 => write!(f, "EVENT_SYSTEM_MINIMIZESTART"),
+            32773u32 => write!(f, "EVENT_OBJECT_FOCUS"),
+            99999u32 => write!(f, "APPLICATION_CLOSING"),
+            EVENT_IDLE_ENTERED => write!(f, "IDLE_ENTERED"),
+            EVENT_IDLE_EXITED => write!(f, "IDLE_EXITED"),
+            EVENT_SESSION_LOCKED => write!(f, "SESSION_LOCKED"),
+            EVENT_SESSION_UNLOCKED => write!(f, "SESSION_UNLOCKED"),
+            EVENT_MANUAL_PAUSE => write!(f, "MANUAL_PAUSE"),
+            EVENT_MANUAL_RESUME => write!(f, "MANUAL_RESUME"),
+            EVENT_SUSPENDED => write!(f, "SUSPENDED"),
+            _ => Err(std::fmt::Error),
+        }
+    }
+}
+
+impl Display for WindowEventType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let readable_event_code = format!("{:?}", self.event_code);
+        write!(f, "{}", readable_event_code)
+    }
+}
+
+/// Metadata about a freshly stored screenshot, broadcast alongside
+/// [`WindowEvent`] so a UI layer (the Tauri event bridge) can react to new
+/// captures without polling `db::crud` or handling the PNG bytes itself.
+#[derive(Debug, Clone, Serialize)]
+pub struct ScreenshotCaptured {
+    pub id: i64,
+    pub app_id: i64,
+    pub created_at_sec: i64,
+}
+
+/// Snapshot of a window's identity, carried by whichever [`WindowEvent`]
+/// variant needs to name the window it's about.
+#[derive(Debug, Clone, Serialize)]
+pub struct WindowInfo {
+    pub name: String,
+    pub title: String,
+    pub path: String,
+    /// The owning process's command line, when it could be read from its
+    /// PEB. Lets callers tell apart distinct workloads under the same
+    /// executable (e.g. different Electron apps, `python.exe` scripts).
+    pub command_line: Option<String>,
+    /// Basename of the parent process's executable, when its pid could be
+    /// resolved and is still running.
+    pub parent_name: Option<String>,
+}
+
+/// The closed set of things the platform hook thread, the idle watcher and
+/// the session watcher can send down the shared channel. Replaces the old
+/// `Box<dyn WindowEvent + Send>` + downcast dance with a single enum so
+/// `process_events` can match exhaustively instead of silently dropping
+/// event kinds the hook wasn't narrowed to recognize.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type")]
+pub enum WindowEvent {
+    /// `EVENT_SYSTEM_FOREGROUND`: a window became the foreground window.
+    Foreground {
+        hwnd: isize,
+        window: WindowInfo,
+        timestamp_sec: i64,
+    },
+    /// `EVENT_OBJECT_NAMECHANGE` on the current foreground window: its title
+    /// changed without a new window taking focus.
+    TitleChanged {
+        hwnd: isize,
+        window: WindowInfo,
+        timestamp_sec: i64,
+    },
+    /// `EVENT_SYSTEM_MINIMIZESTART`: the foreground window was minimized.
+    Minimized { hwnd: isize, timestamp_sec: i64 },
+    /// `EVENT_SYSTEM_MINIMIZEEND`: a window was restored from minimized.
+    Restored {
+        hwnd: isize,
+        window: WindowInfo,
+        timestamp_sec: i64,
+    },
+    /// `EVENT_OBJECT_DESTROY`: a tracked window's handle was torn down.
+    Destroyed { hwnd: isize, timestamp_sec: i64 },
+    /// From `platform::win::presence`'s idle watcher: idle duration crossed
+    /// the configured threshold, in either direction.
+    IdleTransition { idle: bool, timestamp_sec: i64 },
+    /// From `platform::win::presence`'s session watcher, on
+    /// `WM_WTSSESSION_CHANGE` lock/unlock notifications.
+    SessionLock { locked: bool, timestamp_sec: i64 },
+    /// Injected by a UI affordance (the tray's "Pause tracking" item) rather
+    /// than any platform hook, so a user can suspend accounting without
+    /// minimizing or locking the session.
+    ManualPause { paused: bool, timestamp_sec: i64 },
+}
+
+impl WindowEvent {
+    /// The hwnd a variant is about, for variants that carry one. Presence
+    /// transitions aren't about any particular window, hence `Option`.
+    pub fn hwnd(&self) -> Option<isize> {
+        match self {
+            WindowEvent::Foreground { hwnd, .. }
+            | WindowEvent::TitleChanged { hwnd, .. }
+            | WindowEvent::Minimized { hwnd, .. }
+            | WindowEvent::Restored { hwnd, .. }
+            | WindowEvent::Destroyed { hwnd, .. } => Some(*hwnd),
+            WindowEvent::IdleTransition { .. }
+            | WindowEvent::SessionLock { .. }
+            | WindowEvent::ManualPause { .. } => None,
+        }
+    }
+
+    pub fn event_type(&self) -> WindowEventType {
+        let event_code = match self {
+            WindowEvent::Foreground { .. } => EVENT_SYSTEM_FOREGROUND,
+            WindowEvent::TitleChanged { .. } => EVENT_OBJECT_NAMECHANGE,
+            WindowEvent::Minimized { .. } => EVENT_SYSTEM_MINIMIZESTART,
+            WindowEvent::Restored { .. } => EVENT_SYSTEM_MINIMIZEEND,
+            WindowEvent::Destroyed { .. } => EVENT_OBJECT_DESTROY,
+            WindowEvent::IdleTransition { idle, .. } => {
+                if *idle {
+                    EVENT_IDLE_ENTERED
+                } else {
+                    EVENT_IDLE_EXITED
+                }
+            }
+            WindowEvent::SessionLock { locked, .. } => {
+                if *locked {
+                    EVENT_SESSION_LOCKED
+                } else {
+                    EVENT_SESSION_UNLOCKED
+                }
+            }
+            WindowEvent::ManualPause { paused, .. } => {
+                if *paused {
+                    EVENT_MANUAL_PAUSE
+                } else {
+                    EVENT_MANUAL_RESUME
+                }
+            }
+        };
+        WindowEventType { event_code }
+    }
+}
@@ -0,0 +1,746 @@
+use crate::clocks::{Clocks, RealClocks};
+use crate::db::crud::{
+    create_app, create_screenshot, create_window_event_with_timestamp, get_saved_app,
+    update_app_identity, update_app_path,
+};
+use crate::db::models::{App, NewScreenshot};
+use crate::phash;
+use crate::platform::screenshot_window;
+use crate::tracker::events::{
+    EVENT_SUSPENDED, ScreenshotCaptured, WindowEvent, WindowEventType, WindowInfo,
+};
+
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tokio::sync::broadcast;
+use tokio::time::Duration;
+use windows::Win32::Foundation::HWND;
+
+use anyhow::Result;
+
+/// How long without input before the machine is considered idle, absent an
+/// explicit override from config. Mirrors `config::default_idle_threshold_secs`.
+const DEFAULT_IDLE_THRESHOLD: Duration = Duration::from_secs(120);
+
+/// Capacity of the broadcast event bus. Generous enough that a slow
+/// subscriber (e.g. the `tui` binary redrawing) doesn't get events dropped
+/// as `Lagged` under a normal burst of window activity. The processor's own
+/// subscription is drained one event at a time, each after the previous
+/// one's DB writes, so a long enough burst can make it lag too.
+const EVENT_CHANNEL_CAPACITY: usize = 256;
+
+/// Default Hamming-distance dedup threshold, absent an override from
+/// config. Mirrors `config::default_screenshot_hash_distance_threshold`.
+const DEFAULT_SCREENSHOT_HASH_DISTANCE_THRESHOLD: u32 = 10;
+
+/// Default disk-vs-DB choice for captures, absent an override from config.
+/// Mirrors `config::default_screenshot_to_disk`.
+const DEFAULT_SCREENSHOT_TO_DISK: bool = true;
+
+/// The last window known to be foreground, kept around so time accounting
+/// can resume against it once a suspension (idle, lock, minimize) clears.
+struct ForegroundSnapshot {
+    hwnd: isize,
+    window: WindowInfo,
+    /// The app this window resolved to the last time a window_event row was
+    /// written for it, i.e. while the processor wasn't suspended. `None`
+    /// when the window became foreground while already suspended, so a
+    /// suspension boundary is only written against an app that actually has
+    /// an open interval to close.
+    app_id: Option<i64>,
+}
+
+/// Join handles for every task a running processor spawned, so a caller that
+/// wants to reconfigure (a new DB pool, a new idle threshold, ...) can tear
+/// one instance down before starting its replacement instead of ending up
+/// with two concurrent writers. `hook_thread`/`session_thread` wrap blocking
+/// `GetMessageW` loops, for which `JoinHandle::abort` is a documented no-op
+/// (it detaches the OS thread without interrupting it) — those are instead
+/// unblocked by posting `WM_QUIT` to the thread id captured at spawn time,
+/// via `platform::post_quit_to_thread`.
+pub struct ProcessorHandle {
+    hook_thread: tokio::task::JoinHandle<()>,
+    hook_thread_id: Option<u32>,
+    session_thread: tokio::task::JoinHandle<()>,
+    session_thread_id: Option<u32>,
+    idle_watcher: crate::platform::IdleWatcherHandle,
+    event_loop: tokio::task::JoinHandle<()>,
+    /// Extra tasks a caller registers via `track` (e.g. its own event-relay
+    /// loops reading from this processor's broadcast channels), aborted
+    /// alongside this processor's own tasks so reconfiguring doesn't leave
+    /// them forwarding from a channel whose only producer just stopped.
+    tracked_tasks: Vec<tokio::task::JoinHandle<()>>,
+}
+
+impl ProcessorHandle {
+    /// Registers an additional task to be aborted when this handle is
+    /// stopped. Intended for a caller's own loops relaying this processor's
+    /// broadcast events onward (e.g. to a UI), which would otherwise outlive
+    /// the processor they're reading from.
+    pub fn track(&mut self, task: tokio::task::JoinHandle<()>) {
+        self.tracked_tasks.push(task);
+    }
+
+    pub fn stop(self) {
+        if let Some(thread_id) = self.hook_thread_id {
+            crate::platform::post_quit_to_thread(thread_id);
+        }
+        if let Some(thread_id) = self.session_thread_id {
+            crate::platform::post_quit_to_thread(thread_id);
+        }
+        self.hook_thread.abort();
+        self.session_thread.abort();
+        self.idle_watcher.stop();
+        self.event_loop.abort();
+        for task in self.tracked_tasks {
+            task.abort();
+        }
+    }
+}
+
+pub struct WindowEventProcessor {
+    db_pool: SqlitePool,
+    clocks: Arc<dyn Clocks>,
+    idle_threshold: Duration,
+    current_foreground_window_hwnd: Option<isize>,
+    screenshot_handle: Option<tokio::task::JoinHandle<()>>,
+    screenshot_instants: Arc<Mutex<HashMap<isize, Instant>>>,
+    /// Last dHash stored per app, for dedup against the next capture.
+    last_screenshot_hashes: Arc<Mutex<HashMap<i64, u64>>>,
+    screenshot_hash_distance_threshold: u32,
+    screenshot_to_disk: bool,
+    /// Broadcasts metadata for every screenshot this processor stores in the
+    /// database, for `start_with_handle`'s caller (the Tauri event bridge) to
+    /// relay onward. Disk-mode captures don't hit this — only ones that made
+    /// it into `db::crud::create_screenshot`.
+    screenshot_captured_sender: broadcast::Sender<ScreenshotCaptured>,
+    is_idle: bool,
+    is_locked: bool,
+    is_minimized: bool,
+    is_paused: bool,
+    last_foreground: Option<ForegroundSnapshot>,
+}
+
+impl WindowEventProcessor {
+    pub fn new(db_pool: SqlitePool) -> Self {
+        Self::with_clocks(db_pool, Arc::new(RealClocks))
+    }
+
+    /// Same as `new`, but lets callers (tests) supply their own `Clocks`
+    /// implementation so inserts can be driven under a fake clock.
+    pub fn with_clocks(db_pool: SqlitePool, clocks: Arc<dyn Clocks>) -> Self {
+        Self {
+            db_pool,
+            clocks,
+            idle_threshold: DEFAULT_IDLE_THRESHOLD,
+            current_foreground_window_hwnd: None,
+            screenshot_handle: None,
+            screenshot_instants: Arc::new(Mutex::new(HashMap::new())),
+            last_screenshot_hashes: Arc::new(Mutex::new(HashMap::new())),
+            screenshot_hash_distance_threshold: DEFAULT_SCREENSHOT_HASH_DISTANCE_THRESHOLD,
+            screenshot_to_disk: DEFAULT_SCREENSHOT_TO_DISK,
+            screenshot_captured_sender: broadcast::channel(EVENT_CHANNEL_CAPACITY).0,
+            is_idle: false,
+            is_locked: false,
+            is_minimized: false,
+            is_paused: false,
+            last_foreground: None,
+        }
+    }
+
+    /// Overrides how long without input counts as idle (see
+    /// `config::Config::idle_threshold_secs`).
+    pub fn with_idle_threshold(mut self, idle_threshold: Duration) -> Self {
+        self.idle_threshold = idle_threshold;
+        self
+    }
+
+    /// Overrides the dedup distance and disk-vs-DB choice for screenshots
+    /// (see `config::Config::screenshot_hash_distance_threshold` and
+    /// `config::Config::screenshot_to_disk`).
+    pub fn with_screenshot_config(mut self, hash_distance_threshold: u32, to_disk: bool) -> Self {
+        self.screenshot_hash_distance_threshold = hash_distance_threshold;
+        self.screenshot_to_disk = to_disk;
+        self
+    }
+
+    pub fn start(&self) {
+        self.start_with_events();
+    }
+
+    /// Same as `start`, but also returns a subscriber on the same broadcast
+    /// bus the processor itself consumes, so another consumer (the `tui`
+    /// binary's live view) can observe events in real time instead of
+    /// polling `db::crud` for them.
+    pub fn start_with_events(&self) -> broadcast::Receiver<WindowEvent> {
+        let (_sender, events, _screenshots, handle) = self.start_with_handle();
+        // Nothing reconfigures this caller, so the handle is simply leaked
+        // in place of being stopped — matches `start`'s fire-and-forget use.
+        std::mem::forget(handle);
+        events
+    }
+
+    /// Same as `start_with_events`, but also returns the sending half of the
+    /// broadcast bus, so a caller-owned control surface (e.g. the tray's
+    /// "Pause tracking" menu item) can inject `WindowEvent::ManualPause`
+    /// alongside the events the platform hook, idle watcher and session
+    /// watcher generate on their own. The spawned tasks run until the
+    /// process exits; use `start_with_handle` if they need to be stopped.
+    pub fn start_with_control(
+        &self,
+    ) -> (broadcast::Sender<WindowEvent>, broadcast::Receiver<WindowEvent>) {
+        let (sender, events, _screenshots, handle) = self.start_with_handle();
+        std::mem::forget(handle);
+        (sender, events)
+    }
+
+    /// Same as `start_with_control`, but also returns a subscriber on the
+    /// screenshot-captured bus and a `ProcessorHandle` so a caller that
+    /// reconfigures at runtime (the config IPC commands) can `stop()` this
+    /// instance before spawning its replacement.
+    pub fn start_with_handle(
+        &self,
+    ) -> (
+        broadcast::Sender<WindowEvent>,
+        broadcast::Receiver<WindowEvent>,
+        broadcast::Receiver<ScreenshotCaptured>,
+        ProcessorHandle,
+    ) {
+        let (msg_sender, msg_receiver) = broadcast::channel::<WindowEvent>(EVENT_CHANNEL_CAPACITY);
+        let events = msg_sender.subscribe();
+        let db_pool = self.db_pool.clone();
+        let mut processor = WindowEventProcessor::with_clocks(db_pool, self.clocks.clone())
+            .with_idle_threshold(self.idle_threshold)
+            .with_screenshot_config(self.screenshot_hash_distance_threshold, self.screenshot_to_disk);
+        let screenshots = processor.screenshot_captured_sender.subscribe();
+
+        let (hook_thread_id_tx, hook_thread_id_rx) = std::sync::mpsc::channel();
+        let hook_sender = msg_sender.clone();
+        let hook_thread = tokio::task::spawn_blocking(move || {
+            Self::run_message_loop(hook_sender, hook_thread_id_tx);
+        });
+        let hook_thread_id = hook_thread_id_rx.recv().ok();
+
+        let idle_watcher = crate::platform::spawn_idle_watcher(msg_sender.clone(), self.idle_threshold);
+
+        let (session_thread_id_tx, session_thread_id_rx) = std::sync::mpsc::channel();
+        let session_sender = msg_sender.clone();
+        let session_thread = tokio::task::spawn_blocking(move || {
+            crate::platform::run_session_watcher(session_sender, session_thread_id_tx);
+        });
+        let session_thread_id = session_thread_id_rx.recv().ok();
+
+        let event_loop = tokio::spawn(async move {
+            processor.process_events(msg_receiver).await;
+        });
+
+        let handle = ProcessorHandle {
+            hook_thread,
+            hook_thread_id,
+            session_thread,
+            session_thread_id,
+            idle_watcher,
+            event_loop,
+            tracked_tasks: Vec::new(),
+        };
+
+        (msg_sender, events, screenshots, handle)
+    }
+
+    fn run_message_loop(
+        msg_sender: broadcast::Sender<WindowEvent>,
+        thread_id_tx: std::sync::mpsc::Sender<u32>,
+    ) {
+        use windows::Win32::System::Threading::GetCurrentThreadId;
+        use windows::Win32::UI::Accessibility::UnhookWinEvent;
+        use windows::Win32::UI::WindowsAndMessaging::{
+            DispatchMessageW, GetMessageW, TranslateMessage,
+        };
+
+        let hook = crate::platform::set_win_event_hook(msg_sender)
+            .expect("Failed to set Windows event hook");
+        assert!(!hook.is_invalid(), "Windows event hook is invalid");
+
+        // Reported once the hook is live, so `ProcessorHandle::stop` can post
+        // `WM_QUIT` to this exact thread to unblock `GetMessageW` below —
+        // `JoinHandle::abort` cannot interrupt a thread blocked in it.
+        let _ = thread_id_tx.send(unsafe { GetCurrentThreadId() });
+
+        println!("Windows event hook set successfully");
+        unsafe {
+            let mut msg = std::mem::zeroed();
+            while GetMessageW(&mut msg, None, 0, 0).into() {
+                let _ = TranslateMessage(&msg);
+                DispatchMessageW(&msg);
+            }
+            let _ = UnhookWinEvent(hook);
+            println!("Exiting message loop");
+        }
+    }
+
+    pub async fn process_events(&mut self, mut msg_receiver: broadcast::Receiver<WindowEvent>) {
+        loop {
+            match msg_receiver.recv().await {
+                Ok(window_event) => self.handle_window_event(window_event).await,
+                // A burst outran this subscriber; the dropped events are
+                // lost for it specifically, but the bus itself is healthy.
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    }
+
+    async fn handle_window_event(&mut self, window_event: WindowEvent) {
+        let event_type = window_event.event_type();
+        match window_event {
+            WindowEvent::Foreground {
+                hwnd,
+                window,
+                timestamp_sec,
+            }
+            | WindowEvent::Restored {
+                hwnd,
+                window,
+                timestamp_sec,
+            }
+            | WindowEvent::TitleChanged {
+                hwnd,
+                window,
+                timestamp_sec,
+            } => {
+                if self.is_minimized {
+                    // Recovering from a minimize: force a fresh interval and
+                    // screenshot schedule rather than relying on the hwnd
+                    // having changed, since it usually hasn't.
+                    self.is_minimized = false;
+                    self.current_foreground_window_hwnd = None;
+                }
+                self.handle_foreground_event(hwnd, &window, timestamp_sec, event_type)
+                    .await;
+            }
+            WindowEvent::Minimized { hwnd, .. } => {
+                self.handle_minimized(hwnd).await;
+            }
+            WindowEvent::Destroyed { hwnd, .. } => {
+                self.handle_destroyed(hwnd);
+            }
+            WindowEvent::IdleTransition { idle, .. } => {
+                self.handle_presence_transition(|p| p.is_idle = idle).await;
+            }
+            WindowEvent::SessionLock { locked, .. } => {
+                self.handle_presence_transition(|p| p.is_locked = locked)
+                    .await;
+            }
+            WindowEvent::ManualPause { paused, .. } => {
+                self.handle_presence_transition(|p| p.is_paused = paused)
+                    .await;
+            }
+        }
+    }
+
+    /// Applies a presence flag change, then suspends or resumes time
+    /// accounting if that flipped whether the processor is suspended
+    /// overall. Idle, session-lock and minimize are independent signals —
+    /// any one suspends accounting, and all must clear before it resumes.
+    async fn handle_presence_transition(&mut self, apply: impl FnOnce(&mut Self)) {
+        let was_suspended = self.is_suspended();
+        apply(self);
+        let now_suspended = self.is_suspended();
+
+        if now_suspended && !was_suspended {
+            if let Some(handle) = self.screenshot_handle.take() {
+                handle.abort();
+            }
+            // Close the interval that was open for the previously-foreground
+            // app right here, rather than leaving it to whatever event
+            // happens to land next — `get_app_durations_secs` would
+            // otherwise bridge the whole suspension gap (capped at
+            // `MAX_GAP_SECS`) onto that app's active time.
+            if let Some(snapshot) = &self.last_foreground {
+                if let Some(app_id) = snapshot.app_id {
+                    let timestamp_sec = self.clocks.now_unix_secs();
+                    let _ = create_window_event_with_timestamp(
+                        &self.db_pool,
+                        app_id,
+                        snapshot.window.title.clone(),
+                        WindowEventType::new(EVENT_SUSPENDED),
+                        timestamp_sec,
+                    )
+                    .await;
+                }
+            }
+        } else if !now_suspended && was_suspended {
+            if let Some(snapshot) = self.last_foreground.take() {
+                // Force a fresh registration + screenshot schedule for the
+                // window that regained focus, regardless of whether it's
+                // the same hwnd that was foreground before suspension.
+                self.current_foreground_window_hwnd = None;
+                let timestamp_sec = self.clocks.now_unix_secs();
+                let event_type = WindowEventType::new(
+                    windows::Win32::UI::WindowsAndMessaging::EVENT_SYSTEM_FOREGROUND,
+                );
+                self.handle_foreground_event(
+                    snapshot.hwnd,
+                    &snapshot.window,
+                    timestamp_sec,
+                    event_type,
+                )
+                .await;
+            }
+        }
+    }
+
+    fn is_suspended(&self) -> bool {
+        self.is_idle || self.is_locked || self.is_minimized || self.is_paused
+    }
+
+    async fn handle_minimized(&mut self, hwnd: isize) {
+        // Only the foreground window minimizing should close the interval.
+        if self.current_foreground_window_hwnd != Some(hwnd) {
+            return;
+        }
+        self.handle_presence_transition(|p| p.is_minimized = true)
+            .await;
+    }
+
+    fn handle_destroyed(&mut self, hwnd: isize) {
+        self.screenshot_instants.lock().unwrap().remove(&hwnd);
+        crate::platform::evict_cached_dib_sections(HWND(hwnd as *mut std::ffi::c_void));
+
+        if self.current_foreground_window_hwnd == Some(hwnd) {
+            self.current_foreground_window_hwnd = None;
+            if let Some(handle) = self.screenshot_handle.take() {
+                handle.abort();
+            }
+        }
+    }
+
+    async fn handle_foreground_event(
+        &mut self,
+        hwnd: isize,
+        window: &WindowInfo,
+        timestamp_sec: i64,
+        event_type: WindowEventType,
+    ) {
+        if self.is_suspended() {
+            self.last_foreground = Some(ForegroundSnapshot {
+                hwnd,
+                window: window.clone(),
+                app_id: None,
+            });
+            return;
+        }
+
+        let app_id = match self.find_or_create_app(window).await {
+            Ok(app) => {
+                let app_id = app.id.unwrap();
+                let _ = create_window_event_with_timestamp(
+                    &self.db_pool,
+                    app_id,
+                    window.title.clone(),
+                    event_type,
+                    timestamp_sec,
+                )
+                .await;
+                Some(app_id)
+            }
+            Err(e) => {
+                eprintln!("Error processing foreground event: {}", e);
+                None
+            }
+        };
+
+        self.last_foreground = Some(ForegroundSnapshot {
+            hwnd,
+            window: window.clone(),
+            app_id,
+        });
+
+        if self.current_foreground_window_hwnd != Some(hwnd) {
+            self.current_foreground_window_hwnd = Some(hwnd);
+
+            if let Some(handle) = self.screenshot_handle.take() {
+                handle.abort();
+            }
+            if let Some(app_id) = app_id {
+                self.schedule_screenshot(hwnd, app_id, window.name.clone(), window.title.clone())
+                    .await;
+            }
+        }
+    }
+
+    async fn schedule_screenshot(
+        &mut self,
+        hwnd_val: isize,
+        app_id: i64,
+        app_name: String,
+        window_title: String,
+    ) {
+        let screenshot_instants = Arc::clone(&self.screenshot_instants);
+        let last_screenshot_hashes = Arc::clone(&self.last_screenshot_hashes);
+        let screenshot_interval = Duration::from_secs(10); // TODO get this from config
+        let db_pool = self.db_pool.clone();
+        let hash_distance_threshold = self.screenshot_hash_distance_threshold;
+        let screenshot_to_disk = self.screenshot_to_disk;
+        let screenshot_captured_sender = self.screenshot_captured_sender.clone();
+        let clocks = Arc::clone(&self.clocks);
+
+        let screenshot_task = tokio::task::spawn(async move {
+            loop {
+                if should_take_screenshot(
+                    hwnd_val,
+                    screenshot_instants.clone(),
+                    screenshot_interval,
+                    clocks.as_ref(),
+                ) {
+                    execute_screenshot_on_interval(
+                        hwnd_val,
+                        app_id,
+                        app_name.clone(),
+                        window_title.clone(),
+                        &db_pool,
+                        &last_screenshot_hashes,
+                        hash_distance_threshold,
+                        screenshot_to_disk,
+                        &screenshot_captured_sender,
+                        clocks.as_ref(),
+                    )
+                    .await;
+
+                    let mut screenshot_instants = screenshot_instants.lock().unwrap();
+                    screenshot_instants.insert(hwnd_val, clocks.monotonic());
+                } else {
+                    tokio::time::sleep(get_remaining_time(
+                        hwnd_val,
+                        &screenshot_instants,
+                        screenshot_interval,
+                        clocks.as_ref(),
+                    ))
+                    .await;
+                }
+            }
+        });
+
+        self.screenshot_handle = Some(screenshot_task);
+    }
+
+    async fn find_or_create_app(&self, window: &WindowInfo) -> Result<App> {
+        if let Some(app) =
+            get_saved_app(&self.db_pool, &window.name, window.command_line.as_deref()).await
+        {
+            if app.path != window.path {
+                update_app_path(
+                    &self.db_pool,
+                    &window.name,
+                    window.command_line.as_deref(),
+                    &window.path,
+                )
+                .await?;
+
+                return Ok(App {
+                    id: app.id,
+                    name: app.name,
+                    path: window.path.clone(),
+                    icon: app.icon,
+                    command_line: app.command_line,
+                    parent_name: app.parent_name,
+                });
+            }
+            if app.parent_name != window.parent_name {
+                update_app_identity(
+                    &self.db_pool,
+                    &window.name,
+                    window.command_line.as_deref(),
+                    window.parent_name.as_deref(),
+                )
+                .await?;
+                return Ok(App {
+                    parent_name: window.parent_name.clone(),
+                    ..app
+                });
+            }
+            return Ok(app);
+        }
+
+        let app = App {
+            id: None,
+            name: window.name.clone(),
+            path: window.path.clone(),
+            icon: None,
+            command_line: window.command_line.clone(),
+            parent_name: window.parent_name.clone(),
+        };
+
+        create_app(&self.db_pool, &app).await?;
+
+        get_saved_app(&self.db_pool, &window.name, window.command_line.as_deref())
+            .await
+            .ok_or_else(|| anyhow::anyhow!("Failed to retrieve saved app after creation"))
+    }
+}
+
+fn get_remaining_time(
+    hwnd_val: isize,
+    screenshot_instants: &Arc<Mutex<HashMap<isize, Instant>>>,
+    screenshot_interval: Duration,
+    clocks: &dyn Clocks,
+) -> Duration {
+    let screenshots = screenshot_instants.lock().unwrap();
+    if let Some(last_time) = screenshots.get(&hwnd_val) {
+        let elapsed = clocks.monotonic().saturating_duration_since(*last_time);
+        screenshot_interval.saturating_sub(elapsed)
+    } else {
+        screenshot_interval
+    }
+}
+
+fn should_take_screenshot(
+    hwnd_val: isize,
+    screenshot_instants: Arc<Mutex<HashMap<isize, Instant>>>,
+    screenshot_interval: Duration,
+    clocks: &dyn Clocks,
+) -> bool {
+    let screenshots = screenshot_instants.lock().unwrap();
+    match screenshots.get(&hwnd_val) {
+        Some(last_time) => {
+            clocks.monotonic().saturating_duration_since(*last_time) >= screenshot_interval
+        }
+        None => true,
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn execute_screenshot_on_interval(
+    hwnd_val: isize,
+    app_id: i64,
+    app_name: String,
+    window_title: String,
+    db_pool: &SqlitePool,
+    last_screenshot_hashes: &Arc<Mutex<HashMap<i64, u64>>>,
+    hash_distance_threshold: u32,
+    screenshot_to_disk: bool,
+    screenshot_captured_sender: &broadcast::Sender<ScreenshotCaptured>,
+    clocks: &dyn Clocks,
+) {
+    let result = tokio::task::spawn_blocking(move || {
+        let hwnd = HWND(hwnd_val as *mut std::ffi::c_void);
+        screenshot_window(hwnd)
+    })
+    .await;
+
+    match result {
+        Ok(Some(image)) => {
+            let hash = phash::dhash(&image);
+            let is_duplicate = {
+                let mut hashes = last_screenshot_hashes.lock().unwrap();
+                let is_duplicate = hashes
+                    .get(&app_id)
+                    .is_some_and(|&previous| phash::hamming_distance(previous, hash) < hash_distance_threshold);
+                hashes.insert(app_id, hash);
+                is_duplicate
+            };
+
+            if screenshot_to_disk {
+                if is_duplicate {
+                    return;
+                }
+                let timestamp = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap()
+                    .as_secs();
+
+                let screenshot_path = format!("screenshots/{}_{}.png", app_name, timestamp);
+                let _ = image.save(screenshot_path);
+            } else {
+                let png = if is_duplicate {
+                    Vec::new()
+                } else {
+                    let mut buf = Vec::new();
+                    if image
+                        .write_to(&mut std::io::Cursor::new(&mut buf), image::ImageFormat::Png)
+                        .is_err()
+                    {
+                        eprintln!("Failed to encode screenshot for app: {}", app_name);
+                        return;
+                    }
+                    buf
+                };
+
+                let new_screenshot = NewScreenshot {
+                    app_id,
+                    hwnd: hwnd_val as i64,
+                    window_title: window_title.clone(),
+                    phash: hash as i64,
+                    is_marker: is_duplicate,
+                    png,
+                };
+
+                match create_screenshot(db_pool, &new_screenshot).await {
+                    Ok(id) => {
+                        // No subscribers (no UI attached) is the common case
+                        // for the `intime_core` bin and tests, so a failed
+                        // send is routine, not an error worth logging.
+                        let _ = screenshot_captured_sender.send(ScreenshotCaptured {
+                            id,
+                            app_id,
+                            created_at_sec: clocks.now_unix_secs(),
+                        });
+                    }
+                    Err(e) => {
+                        eprintln!("Failed to store screenshot for app {}: {}", app_name, e);
+                    }
+                }
+            }
+        }
+        Ok(None) => {
+            eprintln!("Failed to take screenshot for app: {}", app_name);
+        }
+        Err(e) => {
+            eprintln!("Screenshot task failed for app {}: {}", app_name, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clocks::SimulatedClocks;
+
+    #[test]
+    fn should_take_screenshot_respects_the_interval_under_a_simulated_clock() {
+        let clocks = SimulatedClocks::new(0);
+        let interval = Duration::from_secs(10);
+        let instants = Arc::new(Mutex::new(HashMap::new()));
+        let hwnd = 1isize;
+
+        assert!(should_take_screenshot(hwnd, instants.clone(), interval, &clocks));
+
+        instants.lock().unwrap().insert(hwnd, clocks.monotonic());
+        assert!(!should_take_screenshot(hwnd, instants.clone(), interval, &clocks));
+
+        clocks.advance(10);
+        assert!(should_take_screenshot(hwnd, instants.clone(), interval, &clocks));
+    }
+
+    #[test]
+    fn get_remaining_time_counts_down_as_the_simulated_clock_advances() {
+        let clocks = SimulatedClocks::new(0);
+        let interval = Duration::from_secs(10);
+        let instants = Arc::new(Mutex::new(HashMap::new()));
+        let hwnd = 1isize;
+        instants.lock().unwrap().insert(hwnd, clocks.monotonic());
+
+        assert_eq!(get_remaining_time(hwnd, &instants, interval, &clocks), interval);
+
+        clocks.advance(4);
+        assert_eq!(
+            get_remaining_time(hwnd, &instants, interval, &clocks),
+            Duration::from_secs(6)
+        );
+
+        clocks.advance(100);
+        assert_eq!(get_remaining_time(hwnd, &instants, interval, &clocks), Duration::ZERO);
+    }
+}
@@ -0,0 +1,284 @@
+//! View state for the `tui` binary, kept free of any `ratatui`/`crossterm`
+//! dependency so it can be driven directly under test: `apply_event` updates
+//! the live foreground window from the broadcast bus, `refresh` re-queries
+//! `db::crud` for aggregates, and `ui::draw` only ever reads the result.
+
+use crate::clocks::Clocks;
+use crate::db::crud;
+use crate::db::models::{AppDuration, Screenshot};
+use crate::tracker::events::{WindowEvent, WindowInfo};
+
+const SECS_PER_DAY: i64 = 86_400;
+/// How many of an app's most recent titles the detail pane shows.
+const RECENT_EVENTS_LIMIT: i64 = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum View {
+    Live,
+    History,
+}
+
+impl View {
+    pub fn toggle(self) -> Self {
+        match self {
+            View::Live => View::History,
+            View::History => View::Live,
+        }
+    }
+}
+
+/// All the state one frame of the dashboard needs to render, for either view.
+pub struct Dashboard {
+    pub view: View,
+    pub live_foreground: Option<WindowInfo>,
+    pub today: Vec<AppDuration>,
+    pub history: Vec<AppDuration>,
+    /// How many days back the history view is showing: 0 is today, 1 is
+    /// yesterday, and so on.
+    pub history_day_offset: i64,
+    pub selected: usize,
+    pub recent_events: Vec<crate::db::models::WindowEvent>,
+    pub detail_thumbnail: Option<Screenshot>,
+    /// Mirrors the tray's "Pause tracking" toggle, so the header can show
+    /// that nothing is being recorded instead of just going quiet.
+    pub paused: bool,
+    /// Whether `/` has put the dashboard into query-typing mode; while true,
+    /// the binary's input loop routes key presses into `search_query`
+    /// instead of the normal navigation bindings.
+    pub search_active: bool,
+    pub search_query: String,
+    /// The query `search_results` was last run for, so the detail pane can
+    /// label them even after `search_active` goes back to false.
+    pub last_search_query: String,
+    pub search_results: Vec<crate::db::models::WindowEvent>,
+}
+
+impl Dashboard {
+    pub fn new() -> Self {
+        Self {
+            view: View::Live,
+            live_foreground: None,
+            today: Vec::new(),
+            history: Vec::new(),
+            history_day_offset: 0,
+            selected: 0,
+            recent_events: Vec::new(),
+            detail_thumbnail: None,
+            paused: false,
+            search_active: false,
+            search_query: String::new(),
+            last_search_query: String::new(),
+            search_results: Vec::new(),
+        }
+    }
+
+    /// The ranked app list for whichever view is currently active.
+    pub fn ranked(&self) -> &[AppDuration] {
+        match self.view {
+            View::Live => &self.today,
+            View::History => &self.history,
+        }
+    }
+
+    pub fn select_next(&mut self) {
+        let len = self.ranked().len();
+        if len > 0 {
+            self.selected = (self.selected + 1) % len;
+        }
+    }
+
+    pub fn select_prev(&mut self) {
+        let len = self.ranked().len();
+        if len > 0 {
+            self.selected = (self.selected + len - 1) % len;
+        }
+    }
+
+    /// Moves the history view's date window; `delta_days` is positive to go
+    /// further back, negative to come forward (clamped at today).
+    pub fn shift_history(&mut self, delta_days: i64) {
+        self.history_day_offset = (self.history_day_offset + delta_days).max(0);
+        self.selected = 0;
+    }
+
+    /// Enters query-typing mode, discarding whatever was previously typed.
+    /// Past results stay on screen until a new search actually runs.
+    pub fn start_search(&mut self) {
+        self.search_active = true;
+        self.search_query.clear();
+    }
+
+    /// Leaves query-typing mode without running a search.
+    pub fn cancel_search(&mut self) {
+        self.search_active = false;
+        self.search_query.clear();
+    }
+
+    pub fn push_search_char(&mut self, c: char) {
+        if self.search_active {
+            self.search_query.push(c);
+        }
+    }
+
+    pub fn pop_search_char(&mut self) {
+        if self.search_active {
+            self.search_query.pop();
+        }
+    }
+
+    /// Runs the typed query through `crud::search_window_events` across all
+    /// of history and leaves query-typing mode, so `ui::draw` can show the
+    /// match list in place of the selected app's recent titles.
+    pub async fn run_search(&mut self, db_pool: &sqlx::SqlitePool) -> anyhow::Result<()> {
+        self.search_results =
+            crud::search_window_events(db_pool, &self.search_query, None, None, RECENT_EVENTS_LIMIT)
+                .await?;
+        self.last_search_query = self.search_query.clone();
+        self.search_active = false;
+        Ok(())
+    }
+
+    /// Applies a live event from the same broadcast bus
+    /// `WindowEventProcessor` consumes. Only the events that carry a window
+    /// identity move the "currently foreground" line; `ManualPause` updates
+    /// `paused` instead, and the remaining presence/teardown events don't
+    /// touch this view state at all.
+    pub fn apply_event(&mut self, event: &WindowEvent) {
+        match event {
+            WindowEvent::Foreground { window, .. }
+            | WindowEvent::Restored { window, .. }
+            | WindowEvent::TitleChanged { window, .. } => {
+                self.live_foreground = Some(window.clone());
+            }
+            WindowEvent::ManualPause { paused, .. } => {
+                self.paused = *paused;
+            }
+            WindowEvent::Minimized { .. }
+            | WindowEvent::Destroyed { .. }
+            | WindowEvent::IdleTransition { .. }
+            | WindowEvent::SessionLock { .. } => {}
+        }
+    }
+
+    /// Re-queries `db::crud` for both views' aggregates and the selected
+    /// app's detail pane. Called on a timer and whenever the selection or
+    /// history range changes.
+    pub async fn refresh(
+        &mut self,
+        db_pool: &sqlx::SqlitePool,
+        clocks: &dyn Clocks,
+    ) -> anyhow::Result<()> {
+        let now = clocks.now_unix_secs();
+
+        let (today_start, today_end) = day_bounds(now, 0);
+        self.today = crud::get_app_durations_secs(db_pool, today_start, today_end).await?;
+
+        let (history_start, history_end) = day_bounds(now, self.history_day_offset);
+        self.history = crud::get_app_durations_secs(db_pool, history_start, history_end).await?;
+
+        match self.ranked().get(self.selected) {
+            Some(app) => {
+                let app_id = app.app_id;
+                self.recent_events =
+                    crud::get_recent_events_for_app(db_pool, app_id, RECENT_EVENTS_LIMIT).await?;
+                self.detail_thumbnail =
+                    crud::get_nearest_screenshot(db_pool, now, Some(app_id)).await?;
+            }
+            None => {
+                self.recent_events.clear();
+                self.detail_thumbnail = None;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for Dashboard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The `[start, end)` unix-second bounds of the day `days_ago` days before
+/// the day containing `now_unix_secs`.
+fn day_bounds(now_unix_secs: i64, days_ago: i64) -> (i64, i64) {
+    let day_start = (now_unix_secs - days_ago * SECS_PER_DAY).div_euclid(SECS_PER_DAY) * SECS_PER_DAY;
+    (day_start, day_start + SECS_PER_DAY)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn day_bounds_covers_the_full_day_containing_now() {
+        let (start, end) = day_bounds(1_700_000_000, 0);
+        assert_eq!(end - start, SECS_PER_DAY);
+        assert!(start <= 1_700_000_000 && 1_700_000_000 < end);
+    }
+
+    #[test]
+    fn shift_history_does_not_go_negative() {
+        let mut dashboard = Dashboard::new();
+        dashboard.shift_history(-5);
+        assert_eq!(dashboard.history_day_offset, 0);
+    }
+
+    #[test]
+    fn select_next_wraps_within_the_active_view() {
+        let mut dashboard = Dashboard::new();
+        dashboard.today = vec![
+            AppDuration {
+                app_id: 1,
+                app_name: "a".into(),
+                active_secs: 10,
+            },
+            AppDuration {
+                app_id: 2,
+                app_name: "b".into(),
+                active_secs: 5,
+            },
+        ];
+        dashboard.select_next();
+        assert_eq!(dashboard.selected, 1);
+        dashboard.select_next();
+        assert_eq!(dashboard.selected, 0);
+    }
+
+    #[test]
+    fn cancel_search_clears_the_typed_query() {
+        let mut dashboard = Dashboard::new();
+        dashboard.start_search();
+        dashboard.push_search_char('x');
+        dashboard.push_search_char('y');
+        assert_eq!(dashboard.search_query, "xy");
+
+        dashboard.cancel_search();
+        assert!(!dashboard.search_active);
+        assert_eq!(dashboard.search_query, "");
+    }
+
+    #[test]
+    fn search_chars_are_ignored_outside_search_mode() {
+        let mut dashboard = Dashboard::new();
+        dashboard.push_search_char('x');
+        assert_eq!(dashboard.search_query, "");
+    }
+
+    #[test]
+    fn apply_event_tracks_manual_pause() {
+        let mut dashboard = Dashboard::new();
+        dashboard.apply_event(&WindowEvent::ManualPause {
+            paused: true,
+            timestamp_sec: 0,
+        });
+        assert!(dashboard.paused);
+
+        dashboard.apply_event(&WindowEvent::ManualPause {
+            paused: false,
+            timestamp_sec: 0,
+        });
+        assert!(!dashboard.paused);
+    }
+}
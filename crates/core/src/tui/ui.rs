@@ -0,0 +1,147 @@
+//! Rendering for the `tui` binary. Kept separate from [`super::app`] so the
+//! view state stays testable without pulling in a `ratatui` terminal.
+
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph};
+
+use super::app::{Dashboard, View};
+
+pub fn draw(frame: &mut Frame, dashboard: &Dashboard) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(0)])
+        .split(frame.area());
+
+    draw_header(frame, chunks[0], dashboard);
+
+    let body = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(45), Constraint::Percentage(55)])
+        .split(chunks[1]);
+
+    draw_ranked_list(frame, body[0], dashboard);
+    draw_detail(frame, body[1], dashboard);
+}
+
+fn draw_header(frame: &mut Frame, area: Rect, dashboard: &Dashboard) {
+    let title = match dashboard.view {
+        View::Live => "intime — live  (Tab: history, /: search, q: quit)".to_string(),
+        View::History => format!(
+            "intime — history, {} day(s) ago  (Tab: live, \u{2190}/\u{2192}: change day, /: search, q: quit)",
+            dashboard.history_day_offset
+        ),
+    };
+    let foreground = dashboard
+        .live_foreground
+        .as_ref()
+        .map(|w| format!("Foreground: {} \u{2014} {}", w.name, w.title))
+        .unwrap_or_else(|| "Foreground: (none)".to_string());
+
+    let mut lines = vec![
+        Line::from(Span::styled(title, Style::default().add_modifier(Modifier::BOLD))),
+        Line::from(foreground),
+    ];
+    if dashboard.paused {
+        lines.push(Line::from(Span::styled(
+            "Tracking paused",
+            Style::default().add_modifier(Modifier::BOLD),
+        )));
+    }
+    if dashboard.search_active {
+        lines.push(Line::from(format!(
+            "Search: {}_  (Enter: run, Esc: cancel)",
+            dashboard.search_query
+        )));
+    }
+
+    let header = Paragraph::new(lines)
+    .block(Block::default().borders(Borders::ALL));
+    frame.render_widget(header, area);
+}
+
+fn draw_ranked_list(frame: &mut Frame, area: Rect, dashboard: &Dashboard) {
+    let ranked = dashboard.ranked();
+    let items: Vec<ListItem> = ranked
+        .iter()
+        .map(|app| ListItem::new(format!("{:>8}  {}", format_duration(app.active_secs), app.app_name)))
+        .collect();
+
+    let mut state = ListState::default();
+    if !ranked.is_empty() {
+        state.select(Some(dashboard.selected.min(ranked.len() - 1)));
+    }
+
+    let title = match dashboard.view {
+        View::Live => "Apps today (Up/Down to select)".to_string(),
+        View::History => "Apps that day (Up/Down to select)".to_string(),
+    };
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+
+    frame.render_stateful_widget(list, area, &mut state);
+}
+
+fn draw_detail(frame: &mut Frame, area: Rect, dashboard: &Dashboard) {
+    let selected = dashboard.ranked().get(dashboard.selected);
+
+    let mut lines = vec![Line::from(Span::styled(
+        selected.map(|a| a.app_name.as_str()).unwrap_or("(no app selected)").to_string(),
+        Style::default().add_modifier(Modifier::BOLD),
+    ))];
+
+    lines.push(Line::from(match &dashboard.detail_thumbnail {
+        Some(shot) => format!(
+            "Latest thumbnail: {} bytes, captured at unix {}",
+            shot.png.len(),
+            shot.created_at_sec
+        ),
+        None => "Latest thumbnail: none stored yet".to_string(),
+    }));
+    lines.push(Line::from(""));
+    if dashboard.search_results.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "Recent titles:",
+            Style::default().add_modifier(Modifier::ITALIC),
+        )));
+        if dashboard.recent_events.is_empty() {
+            lines.push(Line::from("  (none in this range)"));
+        } else {
+            for event in &dashboard.recent_events {
+                lines.push(Line::from(format!("  {}", event.window_title)));
+            }
+        }
+    } else {
+        lines.push(Line::from(Span::styled(
+            format!("Search results for \"{}\":", dashboard.last_search_query),
+            Style::default().add_modifier(Modifier::ITALIC),
+        )));
+        for event in &dashboard.search_results {
+            lines.push(Line::from(format!("  {} \u{2014} {}", event.app_name, event.window_title)));
+        }
+    }
+
+    let detail = Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title("Detail"));
+    frame.render_widget(detail, area);
+}
+
+/// Formats a second count as `HhMMm`, for both the ranked list and the
+/// `--nogui` summary printed by the `tui` binary.
+pub fn format_duration(total_secs: i64) -> String {
+    let total_secs = total_secs.max(0);
+    format!("{}h{:02}m", total_secs / 3600, (total_secs % 3600) / 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_duration_pads_minutes() {
+        assert_eq!(format_duration(65), "0h01m");
+        assert_eq!(format_duration(3 * 3600 + 5 * 60), "3h05m");
+    }
+}
@@ -1,20 +1,99 @@
 use image::ImageFormat;
 use intime_core::{
-    self as core, db::models::Screenshot, tracker::events::WindowEventType,
+    self as core, db::models::Screenshot, tracker::events::WindowEvent as TrackedEvent,
+    tracker::events::WindowEventType, tracker::window_processor::ProcessorHandle,
     tracker::window_processor::WindowEventProcessor,
 };
 use sqlx::SqlitePool;
 use std::io::Cursor;
-use tauri::{Manager as _, WindowEvent};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::menu::{Menu, MenuItem};
+use tauri::tray::{TrayIconBuilder, TrayIconEvent};
+use tauri::{Emitter as _, Manager as _, WindowEvent};
+use tokio::sync::broadcast;
 
 struct AppState {
+    pool: Mutex<SqlitePool>,
+    tracker_events: Mutex<broadcast::Sender<TrackedEvent>>,
+    processor_handle: Mutex<Option<ProcessorHandle>>,
+    is_paused: AtomicBool,
+}
+
+impl AppState {
+    fn pool(&self) -> SqlitePool {
+        self.pool.lock().unwrap().clone()
+    }
+}
+
+/// Starts a `WindowEventProcessor` matching `config` against `pool`, relays
+/// its events and screenshot metadata to the frontend as Tauri events
+/// instead of leaving it to poll `fetch_window_events`/`get_nearest_screenshot`,
+/// and hands back everything `AppState` needs to track and later replace it.
+fn spawn_processor(
+    app_handle: tauri::AppHandle,
     pool: SqlitePool,
+    config: &core::config::Config,
+) -> (broadcast::Sender<TrackedEvent>, ProcessorHandle) {
+    let processor = WindowEventProcessor::new(pool)
+        .with_idle_threshold(Duration::from_secs(config.idle_threshold_secs))
+        .with_screenshot_config(
+            config.screenshot_hash_distance_threshold,
+            config.screenshot_to_disk,
+        );
+    let (tracker_events, mut events, mut screenshots, mut handle) = processor.start_with_handle();
+
+    let events_relay = tauri::async_runtime::spawn({
+        let app_handle = app_handle.clone();
+        async move {
+            loop {
+                match events.recv().await {
+                    Ok(event) => {
+                        let _ = app_handle.emit("window-event", &event);
+                    }
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        }
+    });
+
+    let screenshots_relay = tauri::async_runtime::spawn(async move {
+        loop {
+            match screenshots.recv().await {
+                Ok(captured) => {
+                    // Metadata only — PNG bytes stay behind `get_nearest_screenshot`
+                    // so a burst of captures doesn't broadcast large payloads.
+                    let _ = app_handle.emit("screenshot-captured", &captured);
+                }
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+
+    // Tracked so `ProcessorHandle::stop` aborts these relays alongside the
+    // processor they read from — otherwise a config-triggered restart would
+    // leave them forwarding from the old (still-live) broadcast channel,
+    // duplicating every live event to the frontend.
+    handle.track(events_relay);
+    handle.track(screenshots_relay);
+
+    (tracker_events, handle)
+}
+
+fn now_unix_secs() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs() as i64
 }
 
 // Helper function to get or create a system app for application-level events
 async fn get_or_create_system_app(pool: &SqlitePool) -> i64 {
     // Try to get existing system app
-    if let Some(app) = core::db::crud::get_saved_app(pool, "System").await {
+    if let Some(app) = core::db::crud::get_saved_app(pool, "System", None).await {
         return app.id.unwrap_or(1);
     }
 
@@ -24,10 +103,12 @@ async fn get_or_create_system_app(pool: &SqlitePool) -> i64 {
         name: "System".to_string(),
         path: "system://application".to_string(),
         icon: None,
+        command_line: None,
+        parent_name: None,
     };
 
     if let Ok(_) = core::db::crud::create_app(pool, &system_app).await {
-        if let Some(created_app) = core::db::crud::get_saved_app(pool, "System").await {
+        if let Some(created_app) = core::db::crud::get_saved_app(pool, "System", None).await {
             return created_app.id.unwrap_or(1);
         }
     }
@@ -45,56 +126,143 @@ async fn fetch_window_events(
 ) -> Result<Vec<core::db::models::WindowEvent>, String> {
     let start_sec = start_ms / 1000;
     let end_sec = end_ms / 1000;
-    core::db::crud::get_window_events_secs(&state.pool, start_sec, end_sec, limit.unwrap_or(2000))
+    core::db::crud::get_window_events_secs(&state.pool(), start_sec, end_sec, limit.unwrap_or(2000))
         .await
         .map_err(|e| e.to_string())
 }
 
+/// Resolves the optional `format` command argument, defaulting to PNG (the
+/// behavior before thumbnailing existed) when absent or unrecognized.
+fn resolve_image_format(format: Option<&str>) -> ImageFormat {
+    match format {
+        Some("webp") => ImageFormat::WebP,
+        Some("jpeg") | Some("jpg") => ImageFormat::Jpeg,
+        _ => ImageFormat::Png,
+    }
+}
+
 #[tauri::command]
 async fn get_nearest_screenshot(
     state: tauri::State<'_, AppState>,
     ts_ms: i64,
     app_id: Option<i64>,
+    format: Option<String>,
+    max_dimension: Option<u32>,
 ) -> Result<Option<Screenshot>, String> {
     let ts_sec = ts_ms / 1000;
-    let res = core::db::crud::get_nearest_screenshot(&state.pool, ts_sec, app_id)
+    let res = core::db::crud::get_nearest_screenshot(&state.pool(), ts_sec, app_id)
         .await
         .map_err(|e| e.to_string())?;
+
+    let target_format = resolve_image_format(format.as_deref());
+
     Ok(res.map(|s| {
         let bytes = s.png;
         let png_sig: [u8; 8] = [137, 80, 78, 71, 13, 10, 26, 10];
-        let png = if bytes.len() >= 8 && &bytes[..8] == &png_sig {
-            // already PNG
-            bytes
-        } else {
-            // try decode and re-encode as PNG
-            match image::load_from_memory(&bytes) {
-                Ok(img) => {
-                    let mut out = Vec::new();
-                    let _ = img.write_to(&mut Cursor::new(&mut out), ImageFormat::Png);
-                    if out.is_empty() {
-                        bytes
-                    } else {
-                        out
-                    }
-                }
-                Err(_) => bytes,
+        let already_png = bytes.len() >= 8 && &bytes[..8] == &png_sig;
+
+        // The common case — PNG requested, no thumbnailing — can skip
+        // decoding entirely and return the stored bytes as-is.
+        if target_format == ImageFormat::Png && max_dimension.is_none() && already_png {
+            return Screenshot { png: bytes, ..s };
+        }
+
+        let encoded = match image::load_from_memory(&bytes) {
+            Ok(img) => {
+                let img = match max_dimension {
+                    Some(max) => img.thumbnail(max, max),
+                    None => img,
+                };
+                let mut out = Vec::new();
+                let _ = img.write_to(&mut Cursor::new(&mut out), target_format);
+                if out.is_empty() { bytes } else { out }
             }
+            Err(_) => bytes,
         };
-        Screenshot {
-            id: s.id,
-            created_at_sec: s.created_at_sec,
-            app_id: s.app_id,
-            png,
-        }
+        Screenshot { png: encoded, ..s }
     }))
 }
 
+#[tauri::command]
+fn get_config() -> Result<core::config::Config, String> {
+    core::config::Config::load()
+}
+
+/// Persists `config`, then gracefully stops the running `WindowEventProcessor`
+/// and spawns its replacement against the (possibly new) pool/settings —
+/// the same restart-a-listener pattern used for any other live-reloaded
+/// background task, so there's never more than one writer for the event and
+/// screenshot tables.
+#[tauri::command]
+async fn save_config(
+    app_handle: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+    config: core::config::Config,
+) -> Result<(), String> {
+    let previous = core::config::Config::load().unwrap_or_default();
+    config.save()?;
+    core::config::set_auto_launch(config.autostart)?;
+
+    // Same database, different passphrase: rekey the already-open pool in
+    // place via `PRAGMA rekey` instead of reconnecting, since `create_pool`
+    // below would otherwise try (and fail) to open the still-old-keyed file
+    // with the new key.
+    #[cfg(feature = "sqlcipher")]
+    if previous.database_url == config.database_url
+        && previous.resolved_encryption_key() != config.resolved_encryption_key()
+    {
+        core::db::pool::change_key(
+            &state.pool(),
+            config.resolved_encryption_key().unwrap_or_default().as_str(),
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+
+    let pool = core::db::pool::create_pool(
+        &config.database_url,
+        config.resolved_encryption_key().as_deref(),
+    )
+    .await
+    .map_err(|e| e.to_string())?;
+
+    if let Some(handle) = state.processor_handle.lock().unwrap().take() {
+        handle.stop();
+    }
+
+    let (tracker_events, handle) = spawn_processor(app_handle, pool.clone(), &config);
+
+    // `spawn_processor` always starts unpaused — replay the tray's pause
+    // state onto the fresh processor so a config save doesn't silently
+    // resume tracking out from under a user who paused it.
+    if state.is_paused.load(Ordering::SeqCst) {
+        let _ = tracker_events.send(TrackedEvent::ManualPause {
+            paused: true,
+            timestamp_sec: now_unix_secs(),
+        });
+    }
+
+    *state.pool.lock().unwrap() = pool;
+    *state.tracker_events.lock().unwrap() = tracker_events;
+    *state.processor_handle.lock().unwrap() = Some(handle);
+
+    Ok(())
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     dotenv::dotenv().ok();
 
     tauri::Builder::default()
+        // Must be registered before any other plugin so a second launch is
+        // caught and redirected here instead of reaching `.setup(...)` and
+        // spawning a second `WindowEventProcessor` against the same pool.
+        .plugin(tauri_plugin_single_instance::init(|app, _args, _cwd| {
+            if let Some(window) = app.get_webview_window("main") {
+                let _ = window.show();
+                let _ = window.set_focus();
+            }
+        }))
         .setup(|app| {
             if cfg!(debug_assertions) {
                 app.handle().plugin(
@@ -106,60 +274,124 @@ pub fn run() {
 
             // Create the database pool and manage it as state.
             let config = core::config::Config::load().unwrap_or_default();
-            let db_url = config.database_url;
-            let pool = tauri::async_runtime::block_on(SqlitePool::connect(&db_url))?;
-            app.manage(AppState { pool: pool.clone() });
-
-            // Start window_processor in the background
-            tauri::async_runtime::spawn({
-                let pool = pool.clone();
-                async move {
-                    // Your window processing logic here
-                    let processor = WindowEventProcessor::new(pool.clone());
-                    processor.start();
-                }
+            if let Err(e) = core::config::set_auto_launch(config.autostart) {
+                eprintln!("Failed to apply autostart setting: {}", e);
+            }
+            let db_url = config.database_url.clone();
+
+            #[cfg(feature = "crash-handler")]
+            core::platform::win::crash_handler::install(&db_url);
+
+            let pool = tauri::async_runtime::block_on(core::db::pool::create_pool(
+                &db_url,
+                config.resolved_encryption_key().as_deref(),
+            ))?;
+
+            // Start window_processor in the background, keeping the control
+            // sender and its handle around so the tray's "Pause tracking"
+            // item can inject `ManualPause` events, and `save_config` can
+            // restart the processor against a new pool/settings.
+            let (tracker_events, handle) = spawn_processor(app.handle().clone(), pool.clone(), &config);
+
+            app.manage(AppState {
+                pool: Mutex::new(pool.clone()),
+                tracker_events: Mutex::new(tracker_events),
+                processor_handle: Mutex::new(Some(handle)),
+                is_paused: AtomicBool::new(false),
             });
 
+            let show_item = MenuItem::with_id(app, "show", "Show", true, None::<&str>)?;
+            let pause_item =
+                MenuItem::with_id(app, "pause", "Pause tracking", true, None::<&str>)?;
+            let quit_item = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
+            let tray_menu = Menu::with_items(app, &[&show_item, &pause_item, &quit_item])?;
+
+            TrayIconBuilder::new()
+                .icon(app.default_window_icon().cloned().unwrap())
+                .menu(&tray_menu)
+                .on_menu_event(|app, event| match event.id.as_ref() {
+                    "show" => {
+                        if let Some(window) = app.get_webview_window("main") {
+                            let _ = window.show();
+                            let _ = window.set_focus();
+                        }
+                    }
+                    "pause" => {
+                        if let Some(state) = app.try_state::<AppState>() {
+                            let now_paused = !state.is_paused.load(Ordering::SeqCst);
+                            state.is_paused.store(now_paused, Ordering::SeqCst);
+                            let _ = state
+                                .tracker_events
+                                .lock()
+                                .unwrap()
+                                .send(TrackedEvent::ManualPause {
+                                    paused: now_paused,
+                                    timestamp_sec: now_unix_secs(),
+                                });
+                        }
+                    }
+                    "quit" => {
+                        if let Some(state) = app.try_state::<AppState>() {
+                            let pool = state.pool();
+                            tauri::async_runtime::block_on(flush_closing_event(&pool));
+                            if let Some(handle) = state.processor_handle.lock().unwrap().take() {
+                                handle.stop();
+                            }
+                        }
+                        app.exit(0);
+                    }
+                    _ => {}
+                })
+                .on_tray_icon_event(|tray, event| {
+                    if let TrayIconEvent::Click { .. } = event {
+                        if let Some(window) = tray.app_handle().get_webview_window("main") {
+                            let _ = window.show();
+                            let _ = window.set_focus();
+                        }
+                    }
+                })
+                .build(app)?;
+
             Ok(())
         })
         .on_window_event(|window, event| {
             match event {
-                WindowEvent::CloseRequested { .. } => {
-                    // Handle close requested event - insert a Close window event
-                    let app_handle = window.app_handle();
-                    if let Some(app_state) = app_handle.try_state::<AppState>() {
-                        let pool = app_state.pool.clone();
-
-                        tauri::async_runtime::spawn(async move {
-                            // Create a "Close" window event to mark the end of the session
-                            // I want to refactor this later somehow.
-                            let close_event = WindowEventType::new(99999);
-
-                            // Get the most recent app_id, or create a system app as fallback
-                            let app_id = get_or_create_system_app(&pool).await;
-                            let result = core::db::crud::create_window_event(
-                                &pool,
-                                app_id,
-                                "Application Closing".to_string(),
-                                close_event,
-                            )
-                            .await;
-
-                            if let Err(e) = result {
-                                eprintln!("Failed to create close window event: {}", e);
-                            } else {
-                                println!("Close window event created successfully");
-                            }
-                        });
-                    }
+                WindowEvent::CloseRequested { api, .. } => {
+                    // Tracking should keep running in the background; only
+                    // the "Quit" tray action tears it down. Closing the
+                    // window just hides it.
+                    api.prevent_close();
+                    let _ = window.hide();
                 }
                 _ => {}
             }
         })
         .invoke_handler(tauri::generate_handler![
             fetch_window_events,
-            get_nearest_screenshot
+            get_nearest_screenshot,
+            get_config,
+            save_config
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
+
+/// Inserts the synthetic "Application Closing" window event that used to
+/// run on every window close, now reserved for an actual quit.
+async fn flush_closing_event(pool: &SqlitePool) {
+    let close_event = WindowEventType::new(99999);
+    let app_id = get_or_create_system_app(pool).await;
+    let result = core::db::crud::create_window_event(
+        pool,
+        app_id,
+        "Application Closing".to_string(),
+        close_event,
+    )
+    .await;
+
+    if let Err(e) = result {
+        eprintln!("Failed to create close window event: {}", e);
+    } else {
+        println!("Close window event created successfully");
+    }
+}